@@ -1,11 +1,17 @@
 use aws_sdk_cloudwatchlogs::{Client as CloudWatchClient, types::InputLogEvent};
 use chrono::Utc;
+use opentelemetry::global;
+use opentelemetry::trace::TracerProvider;
+use opentelemetry_otlp::{Protocol, SpanExporter, WithExportConfig};
+use opentelemetry_sdk::{Resource, propagation::TraceContextPropagator, trace::SdkTracerProvider};
 use serde::{Deserialize, Serialize};
 use serde_json::to_string;
 use std::str::FromStr;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::Duration;
-use tokio::sync::mpsc;
-use tokio::time::sleep;
+use tokio::sync::{mpsc, oneshot};
+use tokio::time::{sleep, timeout};
 use tracing::{Event, Subscriber};
 use tracing_subscriber::Layer;
 use tracing_subscriber::layer::{Context, SubscriberExt};
@@ -34,14 +40,92 @@ struct LogEntry {
     level: String,
 }
 
+fn to_input_event(entry: LogEntry) -> InputLogEvent {
+    // Format the log message with level
+    let message = serde_json::from_str(&entry.message)
+        .map(Message::Json)
+        .unwrap_or_else(|_| Message::Text(entry.message.clone()));
+
+    let log_event = LogEvent {
+        level: entry.level,
+        message,
+    };
+
+    let formatted_message = to_string(&log_event).unwrap();
+
+    InputLogEvent::builder()
+        .timestamp(entry.timestamp)
+        .message(formatted_message)
+        .build()
+        .unwrap()
+}
+
+/// Handle on the CloudWatch shipping task, reports its backlog and pushes out
+/// buffered logs and spans before exit
+#[derive(Clone)]
+pub struct LogShipper {
+    entries: mpsc::Sender<LogEntry>,
+    flush: mpsc::Sender<oneshot::Sender<()>>,
+    dropped: Arc<AtomicU64>,
+    // None unless an OTLP collector is configured
+    traces: Option<TraceExporter>,
+}
+
+impl LogShipper {
+    /// Entries queued by the layer that the shipping task has not picked up yet
+    pub fn backlog(&self) -> usize {
+        self.entries.max_capacity() - self.entries.capacity()
+    }
+
+    /// Entries the queue holds before the layer starts dropping them
+    pub fn queue_capacity(&self) -> usize {
+        self.entries.max_capacity()
+    }
+
+    /// Entries discarded since startup because the queue was full
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Ships everything queued so far, returns false if the task is gone or the wait timed out
+    pub async fn flush(&self, wait: Duration) -> bool {
+        let traced = match &self.traces {
+            Some(traces) => traces.flush(wait).await,
+            None => true,
+        };
+
+        let (ack, done) = oneshot::channel();
+        if self.flush.send(ack).await.is_err() {
+            return false;
+        }
+        matches!(timeout(wait, done).await, Ok(Ok(()))) && traced
+    }
+}
+
+/// Spans batched for the OTLP collector, pushed out before exit
+#[derive(Clone)]
+pub struct TraceExporter(SdkTracerProvider);
+
+impl TraceExporter {
+    /// Exports every finished span, returns false if the export failed or the wait timed out
+    pub async fn flush(&self, wait: Duration) -> bool {
+        let provider = self.0.clone();
+        // The batch processor flushes synchronously on its own thread
+        let flushed = tokio::task::spawn_blocking(move || provider.force_flush());
+        matches!(timeout(wait, flushed).await, Ok(Ok(Ok(()))))
+    }
+}
+
 // Our CloudWatch Layer
 struct CloudWatchLayer {
     sender: mpsc::Sender<LogEntry>,
+    dropped: Arc<AtomicU64>,
 }
 
 impl CloudWatchLayer {
-    pub fn new(log_group: String, log_stream: String, batch_size: usize) -> Self {
+    pub fn new(log_group: String, log_stream: String, batch_size: usize) -> (Self, LogShipper) {
         let (sender, mut receiver) = mpsc::channel::<LogEntry>(100);
+        let (flush, mut flush_requests) = mpsc::channel::<oneshot::Sender<()>>(1);
 
         // Spawn background task to send logs to CloudWatch
         tokio::spawn(async move {
@@ -68,30 +152,21 @@ impl CloudWatchLayer {
             loop {
                 tokio::select! {
                     Some(entry) = receiver.recv() => {
-                        // Format the log message with level
-                        let message = serde_json::from_str(&entry.message)
-                            .map(Message::Json)
-                            .unwrap_or_else(|_| Message::Text(entry.message.clone()));
-
-                        let log_event = LogEvent {
-                            level: entry.level.clone(),
-                            message,
-                        };
-
-                        let formatted_message = to_string(&log_event).unwrap();
-
-                        log_batch.push(
-                            InputLogEvent::builder()
-                                .timestamp(entry.timestamp)
-                                .message(formatted_message)
-                                .build().unwrap(),
-                        );
+                        log_batch.push(to_input_event(entry));
 
                         // Send batch if it reaches the specified size
                         if log_batch.len() >= batch_size {
                             send_logs(&client, &log_group, &log_stream, &mut log_batch, &mut sequence_token).await;
                         }
                     }
+                    Some(ack) = flush_requests.recv() => {
+                        // Take whatever the layer queued before the request, then ship it all
+                        while let Ok(entry) = receiver.try_recv() {
+                            log_batch.push(to_input_event(entry));
+                        }
+                        send_logs(&client, &log_group, &log_stream, &mut log_batch, &mut sequence_token).await;
+                        let _ = ack.send(());
+                    }
                     _ = sleep(Duration::from_secs(5)) => {
                         // Flush any pending logs after timeout
                         if !log_batch.is_empty() {
@@ -102,7 +177,9 @@ impl CloudWatchLayer {
             }
         });
 
-        CloudWatchLayer { sender }
+        let dropped = Arc::new(AtomicU64::new(0));
+        let shipper = LogShipper { entries: sender.clone(), flush, dropped: dropped.clone(), traces: None };
+        (CloudWatchLayer { sender, dropped }, shipper)
     }
 }
 
@@ -179,41 +256,155 @@ where
             // Get current timestamp in milliseconds
             let timestamp = Utc::now().timestamp_millis();
 
-            // Send log entry to processing task
-            let _ = self.sender.try_send(LogEntry {
+            // Send log entry to processing task, counting what a full queue turns away
+            let entry = LogEntry {
                 timestamp,
                 message,
                 level: level.to_string(),
-            });
+            };
+            if self.sender.try_send(entry).is_err() {
+                self.dropped.fetch_add(1, Ordering::Relaxed);
+            }
         }
     }
 }
 
 
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OtlpProtocol {
+    #[default]
+    Grpc,
+    // Binary protobuf over HTTP
+    Http,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OtlpConfig {
+    // Collector address, the exporter default is http://localhost:4317 for gRPC and
+    // http://localhost:4318/v1/traces for HTTP, or OTEL_EXPORTER_OTLP_ENDPOINT when set
+    pub endpoint: Option<String>,
+    pub protocol: OtlpProtocol,
+    // Defaults to the log stream name
+    pub service_name: Option<String>,
+    pub timeout_secs: u64,
+    // Filters spans separately from `LoggingConfig::level`, request and query spans
+    // are info so a quieter log level still exports them
+    pub level: String,
+}
+
+impl Default for OtlpConfig {
+    fn default() -> Self {
+        OtlpConfig {
+            endpoint: None,
+            protocol: OtlpProtocol::Grpc,
+            service_name: None,
+            timeout_secs: 10,
+            level: "info".to_string(),
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct LoggingConfig {
     pub log_group: String,
     pub log_stream: String,
     pub level: String,
+    // Spans are only exported when a collector is configured
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub otlp: Option<OtlpConfig>,
 }
 
-// Function to set up the tracing subscriber with CloudWatch
-pub fn init_cloudwatch_logger(settings: &LoggingConfig) -> anyhow::Result<()> {
-    let cloudwatch_layer = CloudWatchLayer::new(
+fn with_export_config<B: WithExportConfig>(builder: B, otlp: &OtlpConfig) -> B {
+    let builder = builder.with_timeout(Duration::from_secs(otlp.timeout_secs));
+    match &otlp.endpoint {
+        Some(endpoint) => builder.with_endpoint(endpoint.clone()),
+        None => builder,
+    }
+}
+
+// Batch exports spans to the collector and propagates W3C trace context
+fn init_tracer(settings: &LoggingConfig, otlp: &OtlpConfig) -> anyhow::Result<SdkTracerProvider> {
+    let exporter = match otlp.protocol {
+        OtlpProtocol::Grpc => with_export_config(SpanExporter::builder().with_tonic(), otlp).build()?,
+        OtlpProtocol::Http => {
+            with_export_config(SpanExporter::builder().with_http().with_protocol(Protocol::HttpBinary), otlp).build()?
+        }
+    };
+    let service_name = otlp.service_name.clone().unwrap_or_else(|| settings.log_stream.clone());
+
+    let provider = SdkTracerProvider::builder()
+        .with_batch_exporter(exporter)
+        .with_resource(Resource::builder().with_service_name(service_name).build())
+        .build();
+    global::set_text_map_propagator(TraceContextPropagator::new());
+    global::set_tracer_provider(provider.clone());
+    Ok(provider)
+}
+
+// A missing collector should not take logging down with it
+fn init_traces(settings: &LoggingConfig) -> Option<TraceExporter> {
+    let otlp = settings.otlp.as_ref()?;
+    match init_tracer(settings, otlp) {
+        Ok(provider) => Some(TraceExporter(provider)),
+        Err(e) => {
+            eprintln!("Failed to start the OTLP exporter, spans will not be exported: {e:#}");
+            None
+        }
+    }
+}
+
+fn level_filter(level: &str) -> tracing_subscriber::filter::LevelFilter {
+    let level = tracing::Level::from_str(level).unwrap_or(tracing::Level::INFO);
+    tracing_subscriber::filter::LevelFilter::from_level(level)
+}
+
+fn otel_layer<S>(settings: &LoggingConfig, traces: Option<&TraceExporter>) -> Option<impl Layer<S> + use<S>>
+where
+    S: Subscriber + for<'span> LookupSpan<'span>,
+{
+    let otlp = settings.otlp.as_ref()?;
+    let TraceExporter(provider) = traces?;
+    Some(
+        tracing_opentelemetry::layer()
+            .with_tracer(provider.tracer("tracing"))
+            .with_filter(level_filter(&otlp.level)),
+    )
+}
+
+// Function to set up the tracing subscriber with CloudWatch and, when configured, OTLP
+pub fn init_cloudwatch_logger(settings: &LoggingConfig) -> anyhow::Result<LogShipper> {
+    let (cloudwatch_layer, mut shipper) = CloudWatchLayer::new(
         settings.log_group.to_string(),
         settings.log_stream.to_string(),
         10,
     );
+    shipper.traces = init_traces(settings);
 
     // Create console output layer
     let fmt_layer = tracing_subscriber::fmt::layer().with_target(true);
 
-    let level = tracing::Level::from_str(&settings.level).unwrap_or(tracing::Level::INFO);
-    // Register both layers with the subscriber
+    // Register the layers with the subscriber
+    let _ = tracing_subscriber::registry()
+        .with(otel_layer(settings, shipper.traces.as_ref()))
+        .with(cloudwatch_layer.with_filter(level_filter(&settings.level)))
+        .with(fmt_layer.with_filter(level_filter(&settings.level)))
+        .try_init();
+
+    Ok(shipper)
+}
+
+/// Console logging for when CloudWatch is unavailable, spans are still exported
+/// when a collector is configured
+pub fn init_stdout_logger(settings: &LoggingConfig) -> Option<TraceExporter> {
+    let traces = init_traces(settings);
+    let fmt_layer = tracing_subscriber::fmt::layer().with_target(true);
+
     let _ = tracing_subscriber::registry()
-        .with(cloudwatch_layer.with_filter(tracing_subscriber::filter::LevelFilter::from_level(level)))
-        .with(fmt_layer.with_filter(tracing_subscriber::filter::LevelFilter::from_level(level)))
+        .with(otel_layer(settings, traces.as_ref()))
+        .with(fmt_layer.with_filter(level_filter(&settings.level)))
         .try_init();
 
-    Ok(())
+    traces
 }
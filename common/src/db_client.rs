@@ -5,6 +5,7 @@ use serde::Deserialize;
 use serde::Serialize;
 use sqlx::Pool;
 use sqlx::Postgres;
+use sqlx::postgres::PgListener;
 use sqlx::postgres::PgPoolOptions;
 use std::env;
 use tracing::info;
@@ -125,6 +126,18 @@ impl DBClient {
 
         Ok(Self { pool })
     }
+
+    /// Wait for checked out connections to return, then close every connection in the pool
+    pub async fn close(&self) {
+        self.pool.close().await;
+    }
+
+    /// Open a LISTEN connection using the pool's connect options and subscribe to `channels`
+    pub async fn listener(&self, channels: &[&str]) -> Result<PgListener> {
+        let mut listener = PgListener::connect_with(&self.pool).await?;
+        listener.listen_all(channels.iter().copied()).await?;
+        Ok(listener)
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,39 @@
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Stamps the binary with the commit and build time reported by /health/details
+fn main() {
+    // Docker builds have no .git, the image build passes the commit through GIT_SHA
+    let git_sha = std::env::var("GIT_SHA")
+        .ok()
+        .filter(|sha| !sha.is_empty())
+        .or_else(|| {
+            Command::new("git")
+                .args(["rev-parse", "--short=12", "HEAD"])
+                .output()
+                .ok()
+                .filter(|output| output.status.success())
+                .and_then(|output| String::from_utf8(output.stdout).ok())
+                .map(|sha| sha.trim().to_string())
+        })
+        .unwrap_or_else(|| "unknown".to_string());
+
+    // Reproducible builds pin the timestamp through SOURCE_DATE_EPOCH
+    let built_at = std::env::var("SOURCE_DATE_EPOCH")
+        .ok()
+        .and_then(|epoch| epoch.parse::<i64>().ok())
+        .unwrap_or_else(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|elapsed| elapsed.as_secs() as i64)
+                .unwrap_or_default()
+        });
+
+    println!("cargo:rustc-env=BUILD_GIT_SHA={git_sha}");
+    println!("cargo:rustc-env=BUILD_TIMESTAMP={built_at}");
+    println!("cargo:rerun-if-env-changed=GIT_SHA");
+    println!("cargo:rerun-if-env-changed=SOURCE_DATE_EPOCH");
+    println!("cargo:rerun-if-changed=../.git/HEAD");
+    println!("cargo:rerun-if-changed=../.git/refs/heads");
+    println!("cargo:rerun-if-changed=build.rs");
+}
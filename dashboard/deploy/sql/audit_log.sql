@@ -0,0 +1,19 @@
+-- Access log for trading data, written by the dashboard audit middleware. Applied at startup.
+
+CREATE TABLE IF NOT EXISTS audit_log (
+    id BIGSERIAL PRIMARY KEY,
+    occurred_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    subject TEXT,
+    role TEXT,
+    auth_method TEXT,
+    method TEXT NOT NULL,
+    route TEXT NOT NULL,
+    path TEXT NOT NULL,
+    query JSONB NOT NULL DEFAULT '{}',
+    status INTEGER NOT NULL,
+    response_bytes BIGINT,
+    duration_ms BIGINT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS audit_log_occurred_at_idx ON audit_log (occurred_at);
+CREATE INDEX IF NOT EXISTS audit_log_subject_idx ON audit_log (subject, occurred_at);
@@ -0,0 +1,12 @@
+-- Trade journal owned by the dashboard, one row per strategy. Applied at startup.
+
+CREATE TABLE IF NOT EXISTS journal (
+    local_id UUID PRIMARY KEY,
+    notes TEXT NOT NULL DEFAULT '',
+    tags TEXT[] NOT NULL DEFAULT '{}',
+    attachments JSONB NOT NULL DEFAULT '[]',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS journal_tags_idx ON journal USING GIN (tags);
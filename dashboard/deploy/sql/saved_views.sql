@@ -0,0 +1,12 @@
+-- Named filter presets owned by the dashboard. Applied at startup.
+
+CREATE TABLE IF NOT EXISTS saved_view (
+    id UUID PRIMARY KEY,
+    name TEXT NOT NULL,
+    endpoint TEXT NOT NULL,
+    params JSONB NOT NULL DEFAULT '{}',
+    owner TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    UNIQUE (owner, name)
+);
@@ -0,0 +1,19 @@
+-- Publishes strategy row changes on the `strategy_events` channel for the dashboard's
+-- live updates (/events and /events/ws). The payload is kept small because NOTIFY is
+-- limited to 8000 bytes; the dashboard re-reads the full row by local_id.
+
+CREATE OR REPLACE FUNCTION strategy_notify() RETURNS trigger AS $$
+BEGIN
+    PERFORM pg_notify(
+        'strategy_events',
+        json_build_object('op', TG_OP, 'local_id', NEW.local_id)::text
+    );
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS strategy_notify ON strategy;
+
+CREATE TRIGGER strategy_notify
+AFTER INSERT OR UPDATE ON strategy
+FOR EACH ROW EXECUTE FUNCTION strategy_notify();
@@ -0,0 +1,8 @@
+#!/usr/bin/env bash
+# Regenerates dashboard/openapi.json and the typed frontend client in
+# src/frontend/js (api.d.ts, api_client.js) from the handlers
+set -euo pipefail
+
+cd "$(dirname "$0")/.."
+UPDATE_OPENAPI=1 cargo test -p data-viewer openapi
+git status --short -- openapi.json src/frontend/js/api.d.ts src/frontend/js/api_client.js
@@ -0,0 +1,520 @@
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result, bail};
+use axum::{
+    extract::{FromRequestParts, Request, State},
+    http::{HeaderMap, Uri, header, request::Parts},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use chrono::Utc;
+use common::{parameter_store, settings::SettingsReader};
+use ring::digest::{SHA256, digest};
+use ring::signature::{self, RsaPublicKeyComponents, UnparsedPublicKey};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::{AppState, models::settings::AuthConfig, service::common::AppError, telemetry};
+
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+// EventSource and WebSocket clients cannot set headers, these routes also take ?access_token=
+const TOKEN_QUERY_PREFIX: &str = "/events";
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Role {
+    Viewer,
+    Admin,
+}
+
+impl Role {
+    fn parse(name: &str) -> Option<Role> {
+        match name.trim().to_lowercase().as_str() {
+            "viewer" => Some(Role::Viewer),
+            "admin" => Some(Role::Admin),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum AuthMethod {
+    Jwt,
+    ApiKey,
+    Disabled,
+}
+
+/// Caller identity, inserted into request extensions by `authenticate`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct Principal {
+    pub subject: String,
+    pub role: Role,
+    pub method: AuthMethod,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct Jwk {
+    kid: Option<String>,
+    kty: String,
+    alg: Option<String>,
+    n: Option<String>,
+    e: Option<String>,
+    crv: Option<String>,
+    x: Option<String>,
+    y: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JwkSet {
+    keys: Vec<Jwk>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JwtHeader {
+    alg: String,
+    kid: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Claims {
+    iss: Option<String>,
+    sub: Option<String>,
+    aud: Option<serde_json::Value>,
+    exp: Option<i64>,
+    nbf: Option<i64>,
+    #[serde(flatten)]
+    other: HashMap<String, serde_json::Value>,
+}
+
+#[derive(Deserialize)]
+struct ApiKeyEntry {
+    name: String,
+    key: String,
+    role: Role,
+}
+
+#[derive(Default)]
+struct JwksCache {
+    keys: Vec<Jwk>,
+    fetched_at: Option<Instant>,
+}
+
+/// Validates bearer JWTs against the configured issuer's JWKS and static API keys
+pub(crate) struct Authenticator {
+    config: AuthConfig,
+    jwks: RwLock<JwksCache>,
+    // Keyed by SHA-256 of the key so plaintext keys are not kept in memory
+    api_keys: HashMap<Vec<u8>, Principal>,
+    http: reqwest::Client,
+}
+
+impl Authenticator {
+    #[cfg(test)]
+    pub(crate) fn disabled(config: &AuthConfig) -> Self {
+        Authenticator {
+            config: AuthConfig { enabled: false, ..config.clone() },
+            jwks: RwLock::new(JwksCache::default()),
+            api_keys: HashMap::new(),
+            http: reqwest::Client::new(),
+        }
+    }
+
+    pub async fn load(config: &AuthConfig) -> Result<Self> {
+        let authenticator = Authenticator {
+            config: config.clone(),
+            jwks: RwLock::new(JwksCache::default()),
+            api_keys: HashMap::new(),
+            http: reqwest::Client::builder().timeout(Duration::from_secs(10)).build()?,
+        };
+        if !config.enabled {
+            warn!("Authentication is disabled, every request is treated as an admin");
+            return Ok(authenticator);
+        }
+
+        let api_keys = match &config.api_keys_parameter {
+            Some(name) => load_api_keys(name).await?,
+            None => HashMap::new(),
+        };
+        let jwt_configured = config.jwks_file.is_some() || config.jwks_url.is_some();
+        if jwt_configured && config.issuer.is_none() {
+            bail!("auth.issuer is required when a JWKS source is configured");
+        }
+        if !jwt_configured && api_keys.is_empty() {
+            bail!("auth is enabled but neither a JWKS source nor API keys are configured");
+        }
+
+        let authenticator = Authenticator { api_keys, ..authenticator };
+        if jwt_configured {
+            authenticator.refresh_jwks().await?;
+        }
+        info!(
+            "Authentication enabled with {} JWKS keys and {} API keys",
+            authenticator.jwks.read().map(|c| c.keys.len()).unwrap_or_default(),
+            authenticator.api_keys.len()
+        );
+        Ok(authenticator)
+    }
+
+    async fn refresh_jwks(&self) -> Result<()> {
+        let set: JwkSet = if let Some(path) = &self.config.jwks_file {
+            SettingsReader::read_config_file(path)?
+        } else if let Some(url) = &self.config.jwks_url {
+            self.http.get(url).send().await?.error_for_status()?.json().await?
+        } else {
+            return Ok(());
+        };
+
+        if let Ok(mut cache) = self.jwks.write() {
+            cache.keys = set.keys;
+            cache.fetched_at = Some(Instant::now());
+        }
+        Ok(())
+    }
+
+    fn find_key(&self, kid: Option<&str>) -> Option<Jwk> {
+        let cache = self.jwks.read().ok()?;
+        match kid {
+            Some(kid) => cache.keys.iter().find(|k| k.kid.as_deref() == Some(kid)).cloned(),
+            None if cache.keys.len() == 1 => cache.keys.first().cloned(),
+            None => None,
+        }
+    }
+
+    // Unknown key ids usually mean the issuer rotated keys, refetch at most once per interval
+    async fn key_for(&self, kid: Option<&str>) -> Option<Jwk> {
+        let cached = self.find_key(kid);
+        telemetry::cache_lookup("jwks", cached.is_some());
+        if cached.is_some() {
+            return cached;
+        }
+        let stale = self
+            .jwks
+            .read()
+            .ok()?
+            .fetched_at
+            .is_none_or(|at| at.elapsed() >= Duration::from_secs(self.config.jwks_refresh_secs));
+        if self.config.jwks_url.is_some() && stale {
+            if let Err(e) = self.refresh_jwks().await {
+                warn!("Failed to refresh JWKS: {e:#}");
+            }
+            return self.find_key(kid);
+        }
+        None
+    }
+
+    async fn verify_jwt(&self, token: &str) -> Result<Principal, AppError> {
+        let invalid = |reason: &str| AppError::Unauthorized(format!("Invalid token: {reason}"));
+
+        let mut parts = token.split('.');
+        let (Some(header), Some(payload), Some(sig), None) = (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Err(invalid("malformed"));
+        };
+        let decode = |part: &str| URL_SAFE_NO_PAD.decode(part).map_err(|_| invalid("malformed"));
+        let jwt_header: JwtHeader = serde_json::from_slice(&decode(header)?).map_err(|_| invalid("malformed header"))?;
+        let key = self.key_for(jwt_header.kid.as_deref()).await.ok_or_else(|| invalid("unknown signing key"))?;
+
+        let message = &token[..header.len() + 1 + payload.len()];
+        if !verify_signature(&key, &jwt_header.alg, message.as_bytes(), &decode(sig)?) {
+            return Err(invalid("bad signature"));
+        }
+
+        let claims: Claims = serde_json::from_slice(&decode(payload)?).map_err(|_| invalid("malformed claims"))?;
+        check_claims(&claims, &self.config, Utc::now().timestamp()).map_err(|reason| invalid(&reason))?;
+
+        let role = roles_from(&claims, &self.config.roles_claim)
+            .into_iter()
+            .max()
+            .ok_or_else(|| AppError::Forbidden("Token carries no dashboard role".to_string()))?;
+        Ok(Principal {
+            subject: claims.sub.unwrap_or_default(),
+            role,
+            method: AuthMethod::Jwt,
+        })
+    }
+
+    pub async fn authenticate(&self, headers: &HeaderMap, uri: &Uri) -> Result<Principal, AppError> {
+        if !self.config.enabled {
+            return Ok(Principal {
+                subject: "anonymous".to_string(),
+                role: Role::Admin,
+                method: AuthMethod::Disabled,
+            });
+        }
+
+        if let Some(key) = headers.get(API_KEY_HEADER).and_then(|v| v.to_str().ok()) {
+            let hashed = digest(&SHA256, key.as_bytes());
+            return self
+                .api_keys
+                .get(hashed.as_ref())
+                .cloned()
+                .ok_or_else(|| AppError::Unauthorized("Invalid API key".to_string()));
+        }
+
+        let bearer = headers
+            .get(header::AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "))
+            .map(str::to_string);
+        let token = bearer.or_else(|| {
+            uri.path()
+                .starts_with(TOKEN_QUERY_PREFIX)
+                .then(|| query_token(uri.query().unwrap_or_default()))
+                .flatten()
+        });
+
+        match token {
+            Some(token) => self.verify_jwt(token.trim()).await,
+            None => Err(AppError::Unauthorized("Missing bearer token or API key".to_string())),
+        }
+    }
+}
+
+async fn load_api_keys(name: &str) -> Result<HashMap<Vec<u8>, Principal>> {
+    let raw = parameter_store::get_parameter(name, true).await?;
+    let entries: Vec<ApiKeyEntry> = serde_json::from_str(&raw).context("API keys parameter is not a JSON list")?;
+    Ok(entries
+        .into_iter()
+        .map(|entry| {
+            let hashed = digest(&SHA256, entry.key.as_bytes()).as_ref().to_vec();
+            let principal = Principal {
+                subject: entry.name,
+                role: entry.role,
+                method: AuthMethod::ApiKey,
+            };
+            (hashed, principal)
+        })
+        .collect())
+}
+
+fn query_token(query: &str) -> Option<String> {
+    serde_urlencoded::from_str::<Vec<(String, String)>>(query)
+        .ok()?
+        .into_iter()
+        .find(|(k, _)| k == "access_token")
+        .map(|(_, v)| v)
+}
+
+fn verify_signature(key: &Jwk, alg: &str, message: &[u8], sig: &[u8]) -> bool {
+    if key.alg.as_deref().is_some_and(|a| a != alg) {
+        return false;
+    }
+    let decode = |v: &Option<String>| v.as_deref().and_then(|v| URL_SAFE_NO_PAD.decode(v).ok());
+
+    match (key.kty.as_str(), alg) {
+        ("RSA", "RS256" | "RS384" | "RS512") => {
+            let (Some(n), Some(e)) = (decode(&key.n), decode(&key.e)) else {
+                return false;
+            };
+            let params = match alg {
+                "RS256" => &signature::RSA_PKCS1_2048_8192_SHA256,
+                "RS384" => &signature::RSA_PKCS1_2048_8192_SHA384,
+                _ => &signature::RSA_PKCS1_2048_8192_SHA512,
+            };
+            RsaPublicKeyComponents { n: &n, e: &e }.verify(params, message, sig).is_ok()
+        }
+        ("EC", "ES256" | "ES384") => {
+            let (Some(x), Some(y)) = (decode(&key.x), decode(&key.y)) else {
+                return false;
+            };
+            let params = match (alg, key.crv.as_deref()) {
+                ("ES256", Some("P-256")) => &signature::ECDSA_P256_SHA256_FIXED,
+                ("ES384", Some("P-384")) => &signature::ECDSA_P384_SHA384_FIXED,
+                _ => return false,
+            };
+            // Uncompressed SEC1 point
+            let point: Vec<u8> = [&[0x04][..], &x, &y].concat();
+            UnparsedPublicKey::new(params, point).verify(message, sig).is_ok()
+        }
+        // Rejects "none" and symmetric algorithms, the JWKS only holds public keys
+        _ => false,
+    }
+}
+
+fn check_claims(claims: &Claims, config: &AuthConfig, now: i64) -> Result<(), String> {
+    if claims.iss.as_deref() != config.issuer.as_deref() {
+        return Err("unexpected issuer".to_string());
+    }
+    if let Some(audience) = &config.audience {
+        let matches = match &claims.aud {
+            Some(serde_json::Value::String(aud)) => aud == audience,
+            Some(serde_json::Value::Array(auds)) => auds.iter().any(|a| a.as_str() == Some(audience)),
+            _ => false,
+        };
+        if !matches {
+            return Err("unexpected audience".to_string());
+        }
+    }
+    match claims.exp {
+        Some(exp) if exp + config.leeway_secs >= now => {}
+        Some(_) => return Err("expired".to_string()),
+        None => return Err("missing exp".to_string()),
+    }
+    if claims.nbf.is_some_and(|nbf| nbf - config.leeway_secs > now) {
+        return Err("not yet valid".to_string());
+    }
+    Ok(())
+}
+
+fn roles_from(claims: &Claims, claim: &str) -> Vec<Role> {
+    match claims.other.get(claim) {
+        Some(serde_json::Value::Array(values)) => values.iter().filter_map(|v| v.as_str()).filter_map(Role::parse).collect(),
+        Some(serde_json::Value::String(values)) => values.split_whitespace().filter_map(Role::parse).collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Requires a viewer or admin on every route it is layered on. The principal is
+/// also copied onto the response for outer layers such as the audit log
+pub(crate) async fn authenticate(State(state): State<Arc<AppState>>, mut request: Request, next: Next) -> Response {
+    match state.auth.authenticate(request.headers(), request.uri()).await {
+        Ok(principal) => {
+            request.extensions_mut().insert(principal.clone());
+            let mut response = next.run(request).await;
+            response.extensions_mut().insert(principal);
+            response
+        }
+        Err(e) => e.into_response(),
+    }
+}
+
+/// Handler argument for routes restricted to admins
+pub(crate) struct AdminOnly(pub Principal);
+
+impl<S> FromRequestParts<S> for AdminOnly
+where
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let principal = parts
+            .extensions
+            .get::<Principal>()
+            .cloned()
+            .ok_or_else(|| AppError::Unauthorized("Authentication required".to_string()))?;
+        if principal.role < Role::Admin {
+            return Err(AppError::Forbidden(format!("{} {} requires the admin role", parts.method, parts.uri.path())));
+        }
+        Ok(AdminOnly(principal))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ring::rand::SystemRandom;
+    use ring::signature::{ECDSA_P256_SHA256_FIXED_SIGNING, EcdsaKeyPair, KeyPair};
+    use serde_json::json;
+
+    fn key_pair() -> EcdsaKeyPair {
+        let rng = SystemRandom::new();
+        let pkcs8 = EcdsaKeyPair::generate_pkcs8(&ECDSA_P256_SHA256_FIXED_SIGNING, &rng).unwrap();
+        EcdsaKeyPair::from_pkcs8(&ECDSA_P256_SHA256_FIXED_SIGNING, pkcs8.as_ref(), &rng).unwrap()
+    }
+
+    fn authenticator(pair: &EcdsaKeyPair, api_keys: HashMap<Vec<u8>, Principal>) -> Authenticator {
+        let point = pair.public_key().as_ref();
+        Authenticator {
+            config: AuthConfig { issuer: Some("https://issuer".to_string()), ..Default::default() },
+            jwks: RwLock::new(JwksCache {
+                keys: vec![Jwk {
+                    kid: Some("test".to_string()),
+                    kty: "EC".to_string(),
+                    alg: Some("ES256".to_string()),
+                    n: None,
+                    e: None,
+                    crv: Some("P-256".to_string()),
+                    x: Some(URL_SAFE_NO_PAD.encode(&point[1..33])),
+                    y: Some(URL_SAFE_NO_PAD.encode(&point[33..])),
+                }],
+                fetched_at: Some(Instant::now()),
+            }),
+            api_keys,
+            http: reqwest::Client::new(),
+        }
+    }
+
+    fn signed_token_with(pair: &EcdsaKeyPair, alg: &str, claims: serde_json::Value) -> String {
+        let header = URL_SAFE_NO_PAD.encode(json!({"alg": alg, "kid": "test"}).to_string());
+        let payload = URL_SAFE_NO_PAD.encode(claims.to_string());
+        let message = format!("{header}.{payload}");
+        let sig = pair.sign(&SystemRandom::new(), message.as_bytes()).unwrap();
+        format!("{message}.{}", URL_SAFE_NO_PAD.encode(sig.as_ref()))
+    }
+
+    fn signed_token(pair: &EcdsaKeyPair, claims: serde_json::Value) -> String {
+        signed_token_with(pair, "ES256", claims)
+    }
+
+    fn viewer_claims() -> serde_json::Value {
+        json!({"iss": "https://issuer", "sub": "ann", "exp": Utc::now().timestamp() + 600, "roles": "viewer"})
+    }
+
+    #[tokio::test]
+    async fn test_es256_token_roles_and_claims() {
+        let pair = key_pair();
+        let auth = authenticator(&pair, HashMap::new());
+        let exp = Utc::now().timestamp() + 600;
+
+        let token = signed_token(&pair, json!({"iss": "https://issuer", "sub": "ann", "exp": exp, "roles": ["viewer", "admin"]}));
+        let principal = auth.verify_jwt(&token).await.ok().unwrap();
+        assert_eq!((principal.subject.as_str(), principal.role), ("ann", Role::Admin));
+
+        let token = signed_token(&pair, json!({"iss": "https://other", "exp": exp, "roles": "viewer"}));
+        assert_eq!(auth.verify_jwt(&token).await.err().map(|e| e.code()), Some("unauthorized"));
+
+        let token = signed_token(&pair, json!({"iss": "https://issuer", "exp": exp - 3600, "roles": "viewer"}));
+        assert_eq!(auth.verify_jwt(&token).await.err().map(|e| e.code()), Some("unauthorized"));
+
+        let token = signed_token(&pair, json!({"iss": "https://issuer", "exp": exp}));
+        assert_eq!(auth.verify_jwt(&token).await.err().map(|e| e.code()), Some("forbidden"));
+    }
+
+    #[tokio::test]
+    async fn test_token_alg_must_match_the_key() {
+        let pair = key_pair();
+        let auth = authenticator(&pair, HashMap::new());
+
+        for alg in ["ES384", "RS256", "HS256", "none"] {
+            let token = signed_token_with(&pair, alg, viewer_claims());
+            assert_eq!(auth.verify_jwt(&token).await.err().map(|e| e.code()), Some("unauthorized"), "{alg}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_api_keys_are_looked_up_by_hash() {
+        let principal = Principal { subject: "ops".to_string(), role: Role::Admin, method: AuthMethod::ApiKey };
+        let api_keys = HashMap::from([(digest(&SHA256, b"s3cret").as_ref().to_vec(), principal)]);
+        let auth = authenticator(&key_pair(), api_keys);
+        let uri: Uri = "/metrics".parse().unwrap();
+        let with_key = |key: &'static str| HeaderMap::from_iter([(API_KEY_HEADER.parse().unwrap(), key.parse().unwrap())]);
+
+        let principal = auth.authenticate(&with_key("s3cret"), &uri).await.ok().unwrap();
+        assert_eq!((principal.subject.as_str(), principal.role, principal.method), ("ops", Role::Admin, AuthMethod::ApiKey));
+
+        let wrong = auth.authenticate(&with_key("guess"), &uri).await;
+        assert_eq!(wrong.err().map(|e| e.code()), Some("unauthorized"));
+    }
+
+    #[tokio::test]
+    async fn test_query_token_is_only_accepted_on_events() {
+        let pair = key_pair();
+        let auth = authenticator(&pair, HashMap::new());
+        let token = signed_token(&pair, viewer_claims());
+        let uri = |path: &str| -> Uri { format!("{path}?access_token={token}").parse().unwrap() };
+
+        let principal = auth.authenticate(&HeaderMap::new(), &uri("/events")).await.ok().unwrap();
+        assert_eq!(principal.role, Role::Viewer);
+
+        for path in ["/metrics", "/api/v1/metrics", "/graphql"] {
+            let rejected = auth.authenticate(&HeaderMap::new(), &uri(path)).await;
+            assert_eq!(rejected.err().map(|e| e.code()), Some("unauthorized"), "{path}");
+        }
+    }
+}
@@ -0,0 +1,69 @@
+use std::collections::BTreeMap;
+
+use anyhow::{Context, Result, bail};
+use chrono::NaiveDate;
+use common::db_client::DBClient;
+use rust_decimal::Decimal;
+use std::str::FromStr;
+
+use tracing::info;
+
+use crate::models::settings::BenchmarkConfig;
+use crate::schema;
+use crate::telemetry::ObserveQuery;
+
+/// Daily closes of the configured benchmark index, loaded once at startup
+#[derive(Debug, Default)]
+pub(crate) struct BenchmarkSeries {
+    pub symbol: String,
+    pub closes: BTreeMap<NaiveDate, Decimal>,
+}
+
+impl BenchmarkSeries {
+    /// None when neither benchmark.csv_file nor benchmark.table is set
+    pub async fn load(config: &BenchmarkConfig, db: &DBClient) -> Result<Option<Self>> {
+        let closes = if let Some(path) = &config.csv_file {
+            read_csv(path)?
+        } else if let Some(table) = &config.table {
+            let query = format!("SELECT date, close FROM {} WHERE symbol = $1", schema::table_name(table)?);
+            sqlx::query_as::<_, (NaiveDate, Decimal)>(&query)
+                .bind(&config.symbol)
+                .fetch_all(&db.pool)
+                .observe("benchmark_closes")
+                .await?
+                .into_iter()
+                .collect()
+        } else {
+            return Ok(None);
+        };
+        info!("Loaded {} benchmark closes for {}", closes.len(), config.symbol);
+
+        Ok(Some(BenchmarkSeries {
+            symbol: config.symbol.clone(),
+            closes,
+        }))
+    }
+
+    pub fn closes_between(&self, from: NaiveDate, to: NaiveDate) -> BTreeMap<NaiveDate, Decimal> {
+        self.closes.range(from..=to).map(|(date, close)| (*date, *close)).collect()
+    }
+}
+
+fn read_csv(path: &str) -> Result<BTreeMap<NaiveDate, Decimal>> {
+    let contents = std::fs::read_to_string(path).context(format!("Failed to read benchmark file {path}"))?;
+    let mut closes = BTreeMap::new();
+    for (idx, line) in contents.lines().enumerate().skip(1) {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let mut cols = line.split(',');
+        let (Some(date), Some(close)) = (cols.next(), cols.next()) else {
+            bail!("{path}:{}: expected `date,close`", idx + 1);
+        };
+        let date = NaiveDate::from_str(date.trim()).context(format!("{path}:{}: bad date", idx + 1))?;
+        let close = Decimal::from_str(close.trim()).context(format!("{path}:{}: bad close", idx + 1))?;
+        closes.insert(date, close);
+    }
+    Ok(closes)
+}
@@ -0,0 +1,118 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Datelike, NaiveDate, Utc, Weekday};
+use chrono_tz::Tz;
+use common::settings::SettingsReader;
+use tracing::info;
+
+use crate::models::settings::CalendarConfig;
+
+pub(crate) const TRADING_DAYS_PER_YEAR: f64 = 252.0;
+
+/// Exchange sessions in the configured timezone, weekends and listed holidays excluded
+#[derive(Debug)]
+pub(crate) struct TradingCalendar {
+    tz: Tz,
+    holidays: BTreeSet<NaiveDate>,
+}
+
+impl Default for TradingCalendar {
+    fn default() -> Self {
+        TradingCalendar {
+            tz: chrono_tz::America::New_York,
+            holidays: BTreeSet::new(),
+        }
+    }
+}
+
+impl TradingCalendar {
+    pub fn load(config: &CalendarConfig) -> Result<Self> {
+        let tz: Tz = config
+            .timezone
+            .parse()
+            .map_err(|e| anyhow!("Unknown session timezone {}: {e}", config.timezone))?;
+        let holidays: BTreeSet<NaiveDate> = match &config.holidays_file {
+            Some(path) => SettingsReader::read_config_file::<Vec<NaiveDate>>(path)?.into_iter().collect(),
+            None => BTreeSet::new(),
+        };
+        info!("Trading calendar in {} with {} holidays", tz.name(), holidays.len());
+        Ok(TradingCalendar { tz, holidays })
+    }
+
+    /// IANA name, suitable for `AT TIME ZONE` in SQL
+    pub fn timezone_name(&self) -> &'static str {
+        self.tz.name()
+    }
+
+    pub fn session_date(&self, ts: DateTime<Utc>) -> NaiveDate {
+        ts.with_timezone(&self.tz).date_naive()
+    }
+
+    pub fn is_trading_day(&self, date: NaiveDate) -> bool {
+        !matches!(date.weekday(), Weekday::Sat | Weekday::Sun) && !self.holidays.contains(&date)
+    }
+
+    pub fn trading_day_on_or_after(&self, date: NaiveDate) -> NaiveDate {
+        let mut d = date;
+        while !self.is_trading_day(d) {
+            match d.succ_opt() {
+                Some(next) => d = next,
+                None => break,
+            }
+        }
+        d
+    }
+
+    pub fn trading_days(&self, from: NaiveDate, to: NaiveDate) -> Vec<NaiveDate> {
+        from.iter_days()
+            .take_while(|d| *d <= to)
+            .filter(|d| self.is_trading_day(*d))
+            .collect()
+    }
+
+    /// Session date a trade is booked on; exits outside a session roll to the next one
+    pub fn booking_date(&self, ts: DateTime<Utc>) -> NaiveDate {
+        self.trading_day_on_or_after(self.session_date(ts))
+    }
+
+    /// Empty bucket per trading day between `from` and `to` inclusive
+    pub fn daily_buckets<T: Default>(&self, from: NaiveDate, to: NaiveDate) -> BTreeMap<NaiveDate, T> {
+        self.trading_days(from, to).into_iter().map(|d| (d, T::default())).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn calendar() -> TradingCalendar {
+        TradingCalendar {
+            holidays: [NaiveDate::from_ymd_opt(2025, 7, 4).unwrap()].into_iter().collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_trading_days_skip_weekends_and_holidays() {
+        let cal = calendar();
+        let days = cal.trading_days(
+            NaiveDate::from_ymd_opt(2025, 7, 1).unwrap(),
+            NaiveDate::from_ymd_opt(2025, 7, 8).unwrap(),
+        );
+        let days: Vec<u32> = days.iter().map(|d| d.day()).collect();
+        assert_eq!(days, vec![1, 2, 3, 7, 8]);
+    }
+
+    #[test]
+    fn test_booking_date_uses_session_timezone_and_rolls_forward() {
+        let cal = calendar();
+        // 01:30 UTC on Saturday is still Friday evening in New York
+        let ts = Utc.with_ymd_and_hms(2025, 7, 12, 1, 30, 0).unwrap();
+        assert_eq!(cal.booking_date(ts), NaiveDate::from_ymd_opt(2025, 7, 11).unwrap());
+        // Independence Day exits are booked on the following Monday
+        let ts = Utc.with_ymd_and_hms(2025, 7, 4, 15, 0, 0).unwrap();
+        assert_eq!(cal.booking_date(ts), NaiveDate::from_ymd_opt(2025, 7, 7).unwrap());
+    }
+}
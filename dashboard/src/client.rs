@@ -0,0 +1,285 @@
+use serde_json::Value;
+use std::fmt::Write;
+
+use crate::service::envelope::{JSON_CONTENT_TYPE, V1_PREFIX};
+
+// Typed frontend client rendered from the OpenAPI document. The output is checked
+// in under frontend/js and kept current by the drift test in `openapi`
+
+pub(crate) const TYPES_FILE: &str = "src/frontend/js/api.d.ts";
+pub(crate) const CLIENT_FILE: &str = "src/frontend/js/api_client.js";
+
+const HEADER: &str = "// Generated from dashboard/openapi.json by dashboard/scripts/generate-client.sh, do not edit\n";
+const SCHEMA_REF: &str = "#/components/schemas/";
+// How the client's JSDoc reaches the declarations next to it
+const TYPES_IMPORT: &str = "import('./api').";
+
+const RUNTIME: &str = r#"
+// Typed wrappers over the /api/v1 routes. Each resolves to the envelope's data
+// and rejects with an ApiError carrying the first problem
+(function () {
+  class ApiError extends Error {
+    constructor(status, problem) {
+      super(problem?.detail || `Request failed with status ${status}`);
+      this.status = status;
+      this.problem = problem;
+    }
+  }
+
+  async function request(method, path, params, body) {
+    const query = new URLSearchParams();
+    for (const [key, value] of Object.entries(params || {})) {
+      if (value !== undefined && value !== null) query.append(key, String(value));
+    }
+    const options = { method };
+    if (body instanceof Blob) {
+      options.body = body;
+    } else if (body !== undefined) {
+      options.body = JSON.stringify(body);
+      options.headers = { 'Content-Type': 'application/json' };
+    }
+
+    const response = await window.apiFetch(query.size ? `${path}?${query}` : path, options);
+    if (response.status === 204) return null;
+    const envelope = await response.json();
+    if (!response.ok || envelope.errors?.length) {
+      throw new ApiError(response.status, envelope.errors?.[0]);
+    }
+    return envelope.data;
+  }
+
+  window.ApiError = ApiError;
+  window.api = {
+"#;
+
+fn union(parts: impl Iterator<Item = String>) -> String {
+    let mut unique: Vec<String> = Vec::new();
+    for part in parts {
+        if !unique.contains(&part) {
+            unique.push(part);
+        }
+    }
+    match unique.len() {
+        0 => "unknown".to_string(),
+        _ => unique.join(" | "),
+    }
+}
+
+fn property_name(name: &str) -> String {
+    if name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        name.to_string()
+    } else {
+        format!("{name:?}")
+    }
+}
+
+// One line per property, for object literals nested inside a type
+fn inline_object(schema: &Value, prefix: &str) -> String {
+    let required = required(schema);
+    let fields: Vec<String> = schema["properties"]
+        .as_object()
+        .into_iter()
+        .flatten()
+        .map(|(name, property)| {
+            let optional = if required.contains(&name.as_str()) { "" } else { "?" };
+            format!("{}{optional}: {}", property_name(name), ts_type(property, prefix))
+        })
+        .collect();
+    format!("{{ {} }}", fields.join("; "))
+}
+
+fn required(schema: &Value) -> Vec<&str> {
+    schema["required"].as_array().into_iter().flatten().filter_map(Value::as_str).collect()
+}
+
+fn primitive(kind: &str, schema: &Value, prefix: &str) -> String {
+    match kind {
+        "string" => "string".to_string(),
+        "integer" | "number" => "number".to_string(),
+        "boolean" => "boolean".to_string(),
+        "null" => "null".to_string(),
+        "array" => {
+            let item = ts_type(&schema["items"], prefix);
+            if item.contains(' ') { format!("({item})[]") } else { format!("{item}[]") }
+        }
+        "object" if schema.get("properties").is_some() => inline_object(schema, prefix),
+        "object" => match schema.get("additionalProperties") {
+            Some(values) if values.is_object() => format!("Record<string, {}>", ts_type(values, prefix)),
+            _ => "Record<string, unknown>".to_string(),
+        },
+        _ => "unknown".to_string(),
+    }
+}
+
+/// TypeScript for a JSON schema, component schemas are referenced by name with `prefix`
+fn ts_type(schema: &Value, prefix: &str) -> String {
+    if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+        return format!("{prefix}{}", reference.trim_start_matches(SCHEMA_REF));
+    }
+    if let Some(variants) = schema.get("oneOf").or_else(|| schema.get("anyOf")).and_then(Value::as_array) {
+        return union(variants.iter().map(|v| ts_type(v, prefix)));
+    }
+    if let Some(values) = schema.get("enum").and_then(Value::as_array) {
+        return union(values.iter().map(Value::to_string));
+    }
+    match schema.get("type") {
+        Some(Value::Array(kinds)) => union(kinds.iter().filter_map(Value::as_str).map(|k| primitive(k, schema, prefix))),
+        Some(Value::String(kind)) => primitive(kind, schema, prefix),
+        _ => "unknown".to_string(),
+    }
+}
+
+fn doc_comment(out: &mut String, indent: &str, schema: &Value) {
+    if let Some(description) = schema.get("description").and_then(Value::as_str) {
+        let _ = writeln!(out, "{indent}/** {} */", description.replace("*/", "* /"));
+    }
+}
+
+/// Declarations for every component schema
+pub(crate) fn render_types(spec: &Value) -> String {
+    let mut out = HEADER.to_string();
+    for (name, schema) in spec["components"]["schemas"].as_object().into_iter().flatten() {
+        out.push('\n');
+        doc_comment(&mut out, "", schema);
+        let Some(properties) = schema.get("properties").and_then(Value::as_object) else {
+            let _ = writeln!(out, "export type {name} = {};", ts_type(schema, ""));
+            continue;
+        };
+        let required = required(schema);
+        let _ = writeln!(out, "export interface {name} {{");
+        for (property, property_schema) in properties {
+            let optional = if required.contains(&property.as_str()) { "" } else { "?" };
+            doc_comment(&mut out, "  ", property_schema);
+            let _ = writeln!(out, "  {}{optional}: {};", property_name(property), ts_type(property_schema, ""));
+        }
+        out.push_str("}\n");
+    }
+    out
+}
+
+fn camel_case(operation_id: &str) -> String {
+    let mut name = String::new();
+    for (i, word) in operation_id.trim_end_matches("_v1").split('_').enumerate() {
+        let mut chars = word.chars();
+        match chars.next() {
+            Some(first) if i > 0 => name.extend(first.to_uppercase().chain(chars)),
+            Some(first) => name.extend(std::iter::once(first).chain(chars)),
+            None => {}
+        }
+    }
+    name
+}
+
+// The success response's data, None when it is not a JSON envelope (streams, upgrades)
+fn data_type(operation: &Value) -> Option<String> {
+    let (status, response) = operation["responses"]
+        .as_object()?
+        .iter()
+        .find(|(status, _)| status.starts_with('2'))?;
+    if status == "204" {
+        return Some("null".to_string());
+    }
+    let schema = &response["content"][JSON_CONTENT_TYPE]["schema"];
+    schema.get("properties").map(|p| ts_type(&p["data"], TYPES_IMPORT))
+}
+
+fn render_operation(out: &mut String, path: &str, method: &str, operation: &Value) {
+    let (Some(operation_id), Some(data)) = (operation["operationId"].as_str(), data_type(operation)) else {
+        return;
+    };
+    let parameters: Vec<&Value> = operation["parameters"].as_array().into_iter().flatten().collect();
+    let path_params: Vec<&str> =
+        parameters.iter().filter(|p| p["in"] == "path").filter_map(|p| p["name"].as_str()).collect();
+    let query_params: Vec<&&Value> = parameters.iter().filter(|p| p["in"] == "query").collect();
+
+    let mut docs = Vec::new();
+    let mut args: Vec<String> = Vec::new();
+    for name in &path_params {
+        docs.push(format!("@param {{string}} {name}"));
+        args.push(name.to_string());
+    }
+    let params = if query_params.is_empty() {
+        "undefined".to_string()
+    } else {
+        let fields: Vec<String> = query_params
+            .iter()
+            .map(|p| {
+                let optional = if p["required"] == true { "" } else { "?" };
+                format!("{}{optional}: {}", p["name"].as_str().unwrap_or_default(), ts_type(&p["schema"], TYPES_IMPORT))
+            })
+            .collect();
+        let all_optional = query_params.iter().all(|p| p["required"] != true);
+        let name = if all_optional { "[params]" } else { "params" };
+        docs.push(format!("@param {{{{ {} }}}} {name}", fields.join(", ")));
+        args.push("params".to_string());
+        "params".to_string()
+    };
+    let body = match operation["requestBody"]["content"].as_object() {
+        Some(content) => {
+            let kind = match content.get(JSON_CONTENT_TYPE) {
+                Some(json) => ts_type(&json["schema"], TYPES_IMPORT),
+                None => "Blob".to_string(),
+            };
+            docs.push(format!("@param {{{kind}}} body"));
+            args.push("body".to_string());
+            "body"
+        }
+        None => "undefined",
+    };
+    docs.push(format!("@returns {{Promise<{data}>}}"));
+
+    let mut url = path.to_string();
+    for name in &path_params {
+        url = url.replace(&format!("{{{name}}}"), &format!("${{encodeURIComponent({name})}}"));
+    }
+
+    let description = operation["responses"]
+        .as_object()
+        .and_then(|responses| responses.values().next())
+        .and_then(|response| response["description"].as_str());
+    out.push_str("    /**\n");
+    if let Some(description) = description {
+        let _ = writeln!(out, "     * {description}");
+    }
+    for line in docs {
+        let _ = writeln!(out, "     * {line}");
+    }
+    out.push_str("     */\n");
+    let _ = writeln!(out, "    {}({}) {{", camel_case(operation_id), args.join(", "));
+    let _ = writeln!(out, "      return request('{}', `{url}`, {params}, {body});", method.to_uppercase());
+    out.push_str("    },\n");
+}
+
+/// `window.api` with one function per JSON operation under /api/v1
+pub(crate) fn render_client(spec: &Value) -> String {
+    let mut out = HEADER.to_string();
+    out.push_str(RUNTIME);
+    for (path, operations) in spec["paths"].as_object().into_iter().flatten() {
+        if !path.starts_with(V1_PREFIX) {
+            continue;
+        }
+        for (method, operation) in operations.as_object().into_iter().flatten() {
+            render_operation(&mut out, path, method, operation);
+        }
+    }
+    out.push_str("  };\n})();\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_schema_types() {
+        let nullable = json!({"oneOf": [{"type": "null"}, {"$ref": "#/components/schemas/Pagination"}]});
+        assert_eq!(ts_type(&nullable, ""), "null | Pagination");
+        let map = json!({"type": ["object", "null"], "additionalProperties": {"type": "number"}});
+        assert_eq!(ts_type(&map, ""), "Record<string, number> | null");
+        let tags = json!({"type": ["array", "null"], "items": {"type": "string"}});
+        assert_eq!(ts_type(&tags, ""), "string[] | null");
+        assert_eq!(ts_type(&json!({"type": "string", "enum": ["Open", "Closed"]}), ""), r#""Open" | "Closed""#);
+        assert_eq!(camel_case("list_journal_v1"), "listJournal");
+    }
+}
@@ -0,0 +1,636 @@
+// Generated from dashboard/openapi.json by dashboard/scripts/generate-client.sh, do not edit
+
+export interface AccountBody {
+  account: AccountResponse;
+}
+
+export interface AccountCashFlows {
+  deposits?: number;
+  dividends?: number;
+  fees?: number;
+  interest?: number;
+}
+
+export interface AccountDailySnapshot {
+  account_id?: string;
+  cash_balance?: number;
+  cash_flows?: AccountCashFlows;
+  currency?: string;
+  date?: string;
+  net_liquidating_value?: number;
+  risk_free_annual?: number;
+}
+
+export interface AccountDay {
+  attribution?: null | CashFlowAttribution;
+  cash_balance: number;
+  date: string;
+  net_liquidating_value: number;
+  risk_free_annual: number;
+}
+
+export interface AccountResponse {
+  response: AccountSeries[];
+}
+
+export interface AccountSeries {
+  account_id: string;
+  currency: string;
+  days: AccountDay[];
+  end_nlv: number;
+  missing_days: string[];
+  start_nlv: number;
+  totals: CashFlowAttribution;
+}
+
+export type AssetType = "Equity" | "EquityOption" | "Future" | "FutureOption";
+
+export interface Attachment {
+  content_type?: string | null;
+  name: string;
+  path: string;
+  size: number;
+  uploaded_at: string;
+}
+
+export interface AuditBody {
+  audit: AuditResponse;
+}
+
+export interface AuditRecord {
+  auth_method?: string | null;
+  duration_ms: number;
+  id: number;
+  method: string;
+  occurred_at: string;
+  path: string;
+  query: Record<string, string>;
+  response_bytes?: number | null;
+  role?: string | null;
+  route: string;
+  status: number;
+  subject?: string | null;
+}
+
+export interface AuditResponse {
+  response: AuditRecord[];
+}
+
+export interface BenchmarkBody {
+  benchmark: BenchmarkResponse;
+}
+
+export interface BenchmarkPoint {
+  benchmark_equity: number;
+  date: string;
+  strategy_equity: number;
+}
+
+export interface BenchmarkResponse {
+  curve: BenchmarkPoint[];
+  from: string;
+  stats: BenchmarkStats;
+  symbol: string;
+  to: string;
+}
+
+export interface BenchmarkStats {
+  alpha_annual?: number | null;
+  beta?: number | null;
+  correlation?: number | null;
+  down_capture?: number | null;
+  information_ratio?: number | null;
+  sample_days: number;
+  tracking_error?: number | null;
+  up_capture?: number | null;
+}
+
+export type BinMethod = "freedman_diaconis" | "fixed" | "quantile";
+
+export interface BootstrapTest {
+  p_value: number;
+  samples: number;
+}
+
+export interface BuildInfo {
+  built_at?: string | null;
+  git_sha: string;
+  version: string;
+}
+
+export interface CashFlowAttribution {
+  deposits: number;
+  dividends: number;
+  fees: number;
+  interest: number;
+  nlv_change: number;
+  trading: number;
+}
+
+export type CheckStatus = "pass" | "warn" | "fail";
+
+export interface ConcentrationRow {
+  breaches: string[];
+  exposure: number;
+  exposure_pct?: number | null;
+  key: string;
+  limit?: null | UnderlyingLimit;
+  net_premium: number;
+  net_premium_pct?: number | null;
+  positions: number;
+}
+
+export interface CorrelationBody {
+  correlation: CorrelationResponse;
+}
+
+export type CorrelationGroup = "symbol" | "strategy_type";
+
+export interface CorrelationResponse {
+  from: string;
+  group_by: CorrelationGroup;
+  labels: string[];
+  matrix: ((number | null)[])[];
+  sample_days: number;
+  to: string;
+}
+
+export type Direction = "Long" | "Short";
+
+export interface DistributionBody {
+  distribution: DistributionResponse;
+}
+
+export interface DistributionResponse {
+  bin_method: BinMethod;
+  currency: string;
+  fees: SeriesDistribution;
+  from: string;
+  holding_hours: SeriesDistribution;
+  net_pnl: SeriesDistribution;
+  roi: SeriesDistribution;
+  to: string;
+}
+
+export interface DrawdownMetrics {
+  max_dd_abs: number;
+  max_dd_pct_base: number;
+  peak_date?: string | null;
+  recovery_days?: number | null;
+  trough_date?: string | null;
+}
+
+export interface ExpectancyMetrics {
+  avg_loss: number;
+  avg_win: number;
+  expectancy_usd: number;
+  median_usd: number;
+  trade_count: number;
+  win_rate?: number | null;
+}
+
+export interface ExposureBucket {
+  current_value: number;
+  key: string;
+  open_value: number;
+  positions: number;
+  unrealized_pnl: number;
+}
+
+export interface ExposureSummary {
+  by_asset_type: ExposureBucket[];
+  by_side: ExposureBucket[];
+  by_underlying: ExposureBucket[];
+}
+
+export interface FeeBucket {
+  avg_fee?: number | null;
+  fee_share_of_gross_profit?: number | null;
+  fees: number;
+  gross_pnl: number;
+  gross_profit: number;
+  key: string;
+  trade_count: number;
+}
+
+export type FeePeriod = "day" | "week" | "month";
+
+export interface FeeReconciliation {
+  account_fees: number;
+  account_id: string;
+  difference: number;
+  snapshot_days: number;
+  trade_fees: number;
+}
+
+export interface FeeReport {
+  by_asset_type: FeeBucket[];
+  by_period: FeeBucket[];
+  by_strategy_type: FeeBucket[];
+  by_symbol: FeeBucket[];
+  currency: string;
+  flipped_trades: FlippedTrade[];
+  from: string;
+  period: FeePeriod;
+  reconciliation: FeeReconciliation[];
+  to: string;
+  totals: FeeBucket;
+}
+
+export interface FeesBody {
+  fees: FeeReport;
+}
+
+export interface FlippedTrade {
+  contract: string;
+  exit_time: string;
+  fee: number;
+  local_id: string;
+  net: number;
+  pnl: number;
+  symbol: string;
+}
+
+export interface Gain {
+  current: number;
+  open: number;
+  target: number;
+}
+
+export interface HealthChecks {
+  database_pool: PoolCheck;
+  database_round_trip: RoundTripCheck;
+  log_shipping: LogShippingCheck;
+  schema: SchemaCheck;
+}
+
+export interface HealthDetails {
+  build: BuildInfo;
+  checks: HealthChecks;
+  config_source: string;
+  shutting_down: boolean;
+  status: CheckStatus;
+}
+
+export interface HistogramBin {
+  count: number;
+  lower: number;
+  upper: number;
+}
+
+export type Instrument = { kind: "Equity" } | { expiry: string; kind: "EquityOption"; right: OptionRight; strike: number } | { kind: "Future"; month?: number | null; year?: number | null } | { contract: string; expiry?: string | null; kind: "FutureOption"; right?: null | OptionRight; strike?: number | null };
+
+export interface JournalBody {
+  journal: JournalResponse;
+}
+
+export interface JournalEntry {
+  attachments: Attachment[];
+  created_at: string;
+  local_id: string;
+  notes: string;
+  tags: string[];
+  updated_at: string;
+}
+
+export interface JournalResponse {
+  response: JournalEntry[];
+}
+
+export interface JournalUpdate {
+  notes?: string | null;
+  tags?: string[] | null;
+}
+
+export interface LogShippingCheck {
+  backlog: number;
+  capacity: number;
+  enabled: boolean;
+  status: CheckStatus;
+}
+
+export interface Loss {
+  lower?: number | null;
+  target: number;
+  upper?: number | null;
+  watermark: number;
+}
+
+export interface Meta {
+  count?: number | null;
+  pagination?: null | Pagination;
+  timings: Timings;
+}
+
+export interface Metadata {
+  asset_type: AssetType;
+  local_id: string;
+  open_price: number;
+  price_effect: PriceEffect;
+  side: Side;
+  status: Status;
+  type: StrategyType;
+  underlying: string;
+}
+
+export interface MetricDelta {
+  absolute?: number | null;
+  base?: number | null;
+  compared?: number | null;
+  relative?: number | null;
+}
+
+export interface MetricsBody {
+  accounts?: Record<string, MetricsResponseBody> | null;
+  comparison?: null | MetricsComparison;
+  metrics: MetricsResponseBody;
+}
+
+export interface MetricsComparison {
+  deltas: Record<string, MetricDelta>;
+  metrics: MetricsResponseBody;
+  significance: SignificanceMetrics;
+}
+
+export interface MetricsResponseBody {
+  currency: string;
+  drawdown: DrawdownMetrics;
+  expectancy: ExpectancyMetrics;
+  from: string;
+  profit_factor: ProfitFactorMetrics;
+  recovery: RecoveryFactorMetrics;
+  sharpe: SharpeMetrics;
+  to: string;
+}
+
+export interface OpenPosition {
+  asset_type: AssetType;
+  contract: string;
+  current_notional: number;
+  current_price: number;
+  days_in_trade: number;
+  direction: Direction;
+  distance_to_stop: number;
+  distance_to_target: number;
+  entry_time: string;
+  local_id: string;
+  open_notional: number;
+  open_price: number;
+  price_effect: PriceEffect;
+  profit_target: number;
+  side: Side;
+  stop: number;
+  strategy_type: StrategyType;
+  symbol: string;
+  underlying: string;
+  unrealized_pct?: number | null;
+  unrealized_pnl: number;
+  watermark: number;
+}
+
+export type OptionRight = "Call" | "Put";
+
+export interface Pagination {
+  limit: number;
+  next_before_id?: number | null;
+}
+
+export interface ParsedSymbol {
+  instrument: Instrument;
+  raw: string;
+  root: string;
+}
+
+export interface Performance {
+  end_price: number;
+  exit_date: string;
+  fee: number;
+  pnl: number;
+  roi: number;
+  start_date: string;
+  start_price: number;
+  strategy: string;
+}
+
+export interface PerformanceBody {
+  performance: PerformanceResponse;
+}
+
+export interface PerformanceResponse {
+  response: Performance[];
+}
+
+export interface PoolCheck {
+  idle: number;
+  max_connections: number;
+  size: number;
+  status: CheckStatus;
+}
+
+export interface PositionsBody {
+  positions: PositionsResponse;
+}
+
+export interface PositionsResponse {
+  as_of: string;
+  exposure: ExposureSummary;
+  response: OpenPosition[];
+}
+
+export type PriceEffect = "Credit" | "Debit";
+
+/** RFC 7807 problem details returned by every error, see `AppError` */
+export interface Problem {
+  code: string;
+  detail: string;
+  status: number;
+  title: string;
+  type: string;
+}
+
+export interface ProfitFactorMetrics {
+  gross_loss: number;
+  gross_profit: number;
+  losses: number;
+  profit_factor?: number | null;
+  trade_count: number;
+  wins: number;
+}
+
+export interface RecoveryFactorMetrics {
+  net_profit: number;
+  recovery_factor?: number | null;
+  reference_max_dd: number;
+}
+
+export interface RiskBody {
+  risk: RiskReport;
+}
+
+export interface RiskData {
+  gain?: Gain;
+  loss?: Loss;
+  side: Side;
+  stats?: Stats;
+}
+
+export interface RiskReport {
+  as_of: string;
+  breach_count: number;
+  by_root: ConcentrationRow[];
+  by_underlying: ConcentrationRow[];
+  currency: string;
+  net_liquidating_value: number;
+}
+
+export interface RoundTripCheck {
+  error?: string | null;
+  latency_ms: number;
+  status: CheckStatus;
+}
+
+export interface SavedView {
+  created_at: string;
+  endpoint: string;
+  id: string;
+  name: string;
+  owner: string;
+  params: Record<string, unknown>;
+  updated_at: string;
+}
+
+export interface SavedViewRequest {
+  endpoint: string;
+  name: string;
+  owner?: string;
+  params?: Record<string, unknown>;
+}
+
+export interface SavedViewResponse {
+  response: SavedView[];
+}
+
+export interface SchemaCheck {
+  error?: string | null;
+  missing_columns: string[];
+  status: CheckStatus;
+}
+
+export interface SeriesDistribution {
+  bins: HistogramBin[];
+  count: number;
+  kurtosis?: number | null;
+  max?: number | null;
+  mean?: number | null;
+  min?: number | null;
+  percentiles: number[];
+  skew?: number | null;
+  std_dev?: number | null;
+}
+
+export interface SharpeMetrics {
+  mean_daily?: number | null;
+  rf_annual: number;
+  sample_days: number;
+  sharpe?: number | null;
+  vol_daily?: number | null;
+}
+
+export type Side = "Call" | "Put" | "Netural";
+
+export interface SignificanceMetrics {
+  bootstrap?: null | BootstrapTest;
+  mean_base?: number | null;
+  mean_compared?: number | null;
+  welch?: null | WelchTest;
+}
+
+export interface Stats {
+  fee?: number;
+  pnl: number;
+  roi: number;
+}
+
+export type Status = "Open" | "Closed";
+
+export interface StrategiesBody {
+  strategies: StrategyResponse;
+}
+
+export interface Strategy {
+  account?: AccountDailySnapshot;
+  contract?: string;
+  entry_time: string;
+  exit_time: string;
+  local_id: string;
+  meta: Metadata;
+  risk: RiskData;
+  status: Status;
+  symbol: string;
+}
+
+export interface StrategyEvent {
+  kind: StrategyEventKind;
+  strategy: Strategy;
+}
+
+export type StrategyEventKind = "Opened" | "Updated" | "Closed";
+
+export interface StrategyResponse {
+  response: Strategy[];
+}
+
+export type StrategyType = "SingleLeg" | "CreditSpread" | "IronCondor" | "CalendarSpread" | "Other";
+
+export interface Symbol {
+  asset_types: string[];
+  closed_trades: number;
+  contracts?: ParsedSymbol[];
+  first_trade: string;
+  last_trade: string;
+  name: string;
+  net_pnl: number;
+  open_positions: number;
+  trade_count: number;
+  win_rate?: number | null;
+}
+
+export interface SymbolResponse {
+  response: Symbol[];
+}
+
+export interface SymbolsBody {
+  symbols: SymbolResponse;
+}
+
+export interface Timings {
+  total_ms: number;
+}
+
+export interface UnderlyingLimit {
+  max_exposure_pct?: number | null;
+  max_net_premium_pct?: number | null;
+  max_positions?: number | null;
+}
+
+export interface ViewsBody {
+  views: SavedViewResponse;
+}
+
+export interface WatermarkDataPoint {
+  value: number;
+  x: string;
+  y: string;
+}
+
+export interface WatermarksBody {
+  max_watermark: number;
+  min_watermark: number;
+  watermarks: WatermarkDataPoint[];
+}
+
+export interface WelchTest {
+  df: number;
+  p_value: number;
+  t: number;
+}
@@ -0,0 +1,252 @@
+// Generated from dashboard/openapi.json by dashboard/scripts/generate-client.sh, do not edit
+
+// Typed wrappers over the /api/v1 routes. Each resolves to the envelope's data
+// and rejects with an ApiError carrying the first problem
+(function () {
+  class ApiError extends Error {
+    constructor(status, problem) {
+      super(problem?.detail || `Request failed with status ${status}`);
+      this.status = status;
+      this.problem = problem;
+    }
+  }
+
+  async function request(method, path, params, body) {
+    const query = new URLSearchParams();
+    for (const [key, value] of Object.entries(params || {})) {
+      if (value !== undefined && value !== null) query.append(key, String(value));
+    }
+    const options = { method };
+    if (body instanceof Blob) {
+      options.body = body;
+    } else if (body !== undefined) {
+      options.body = JSON.stringify(body);
+      options.headers = { 'Content-Type': 'application/json' };
+    }
+
+    const response = await window.apiFetch(query.size ? `${path}?${query}` : path, options);
+    if (response.status === 204) return null;
+    const envelope = await response.json();
+    if (!response.ok || envelope.errors?.length) {
+      throw new ApiError(response.status, envelope.errors?.[0]);
+    }
+    return envelope.data;
+  }
+
+  window.ApiError = ApiError;
+  window.api = {
+    /**
+     * Daily NLV with cash flow attribution per account, tags is rejected
+     * @param {{ from: string, to: string, account_id?: string, tags?: string }} params
+     * @returns {Promise<import('./api').AccountSeries[]>}
+     */
+    account(params) {
+      return request('GET', `/api/v1/account`, params, undefined);
+    },
+    /**
+     * Audit records newest first, admin only
+     * @param {{ from: string, to: string, subject?: string, route?: string, status?: number, limit?: number, before_id?: number }} params
+     * @returns {Promise<import('./api').AuditRecord[]>}
+     */
+    auditLog(params) {
+      return request('GET', `/api/v1/audit`, params, undefined);
+    },
+    /**
+     * Equity curve and statistics against the benchmark
+     * @param {{ from: string, to: string, account_id?: string }} params
+     * @returns {Promise<import('./api').BenchmarkResponse>}
+     */
+    benchmark(params) {
+      return request('GET', `/api/v1/benchmark`, params, undefined);
+    },
+    /**
+     * Clustered correlation matrix of daily P&L
+     * @param {{ from: string, to: string, group_by?: import('./api').CorrelationGroup, account_id?: string }} params
+     * @returns {Promise<import('./api').CorrelationResponse>}
+     */
+    correlation(params) {
+      return request('GET', `/api/v1/correlation`, params, undefined);
+    },
+    /**
+     * Histograms, percentiles and moments per trade series
+     * @param {{ from: string, to: string, account_id?: string, tags?: string, strategy_type?: import('./api').StrategyType, bins?: import('./api').BinMethod, bin_count?: number, bin_width?: number }} params
+     * @returns {Promise<import('./api').DistributionResponse>}
+     */
+    distribution(params) {
+      return request('GET', `/api/v1/distribution`, params, undefined);
+    },
+    /**
+     * Fees by period, symbol, type and asset, reconciled against account snapshots unless tags or strategy_type are set
+     * @param {{ from: string, to: string, account_id?: string, tags?: string, strategy_type?: import('./api').StrategyType, period?: import('./api').FeePeriod }} params
+     * @returns {Promise<import('./api').FeeReport>}
+     */
+    fees(params) {
+      return request('GET', `/api/v1/fees`, params, undefined);
+    },
+    /**
+     * Every check passed or only warned, admin only
+     * @returns {Promise<import('./api').HealthDetails>}
+     */
+    details() {
+      return request('GET', `/api/v1/health/details`, undefined, undefined);
+    },
+    /**
+     * Journal entries
+     * @param {{ tags?: string }} [params]
+     * @returns {Promise<import('./api').JournalEntry[]>}
+     */
+    listJournal(params) {
+      return request('GET', `/api/v1/journal`, params, undefined);
+    },
+    /**
+     * Entry and its attachments removed, admin only
+     * @param {string} local_id
+     * @returns {Promise<null>}
+     */
+    deleteJournal(local_id) {
+      return request('DELETE', `/api/v1/journal/${encodeURIComponent(local_id)}`, undefined, undefined);
+    },
+    /**
+     * The strategy's journal entry
+     * @param {string} local_id
+     * @returns {Promise<import('./api').JournalEntry[]>}
+     */
+    getJournal(local_id) {
+      return request('GET', `/api/v1/journal/${encodeURIComponent(local_id)}`, undefined, undefined);
+    },
+    /**
+     * Entry created, admin only
+     * @param {string} local_id
+     * @param {import('./api').JournalUpdate} body
+     * @returns {Promise<import('./api').JournalEntry[]>}
+     */
+    createJournal(local_id, body) {
+      return request('POST', `/api/v1/journal/${encodeURIComponent(local_id)}`, undefined, body);
+    },
+    /**
+     * Entry updated, admin only
+     * @param {string} local_id
+     * @param {import('./api').JournalUpdate} body
+     * @returns {Promise<import('./api').JournalEntry[]>}
+     */
+    updateJournal(local_id, body) {
+      return request('PUT', `/api/v1/journal/${encodeURIComponent(local_id)}`, undefined, body);
+    },
+    /**
+     * Attachment stored, admin only
+     * @param {string} local_id
+     * @param {{ name: string }} params
+     * @param {Blob} body
+     * @returns {Promise<import('./api').JournalEntry[]>}
+     */
+    uploadAttachment(local_id, params, body) {
+      return request('POST', `/api/v1/journal/${encodeURIComponent(local_id)}/attachments`, params, body);
+    },
+    /**
+     * Risk-adjusted metrics, optionally compared and per account
+     * @param {{ from: string, to: string, account_id?: string, tags?: string, per_account?: boolean, strategy_type?: import('./api').StrategyType, compare_from?: string, compare_to?: string, compare_account_id?: string, compare_tags?: string, compare_strategy_type?: import('./api').StrategyType, view?: string }} params
+     * @returns {Promise<import('./api').MetricsBody>}
+     */
+    metrics(params) {
+      return request('GET', `/api/v1/metrics`, params, undefined);
+    },
+    /**
+     * Per strategy P&L and ROI, amounts in the reporting currency
+     * @param {{ from: string, to: string, is_active: boolean, account_id?: string }} params
+     * @returns {Promise<import('./api').Performance[]>}
+     */
+    performance(params) {
+      return request('GET', `/api/v1/performance`, params, undefined);
+    },
+    /**
+     * Open positions and exposure
+     * @param {{ account_id?: string }} [params]
+     * @returns {Promise<import('./api').PositionsResponse>}
+     */
+    positions(params) {
+      return request('GET', `/api/v1/positions`, params, undefined);
+    },
+    /**
+     * Concentration against the configured limits
+     * @param {{ account_id?: string }} [params]
+     * @returns {Promise<import('./api').RiskReport>}
+     */
+    risk(params) {
+      return request('GET', `/api/v1/risk`, params, undefined);
+    },
+    /**
+     * Strategies traded under the symbol, amounts in the reporting currency
+     * @param {string} symbol
+     * @param {{ from: string, to: string, account_id?: string, tags?: string }} params
+     * @returns {Promise<import('./api').Strategy[]>}
+     */
+    strategy(symbol, params) {
+      return request('GET', `/api/v1/strategy/${encodeURIComponent(symbol)}`, params, undefined);
+    },
+    /**
+     * Traded roots with their contracts and activity
+     * @param {{ account_id?: string, prefix?: string, sort?: import('./api').SymbolSort }} [params]
+     * @returns {Promise<import('./api').Symbol[]>}
+     */
+    symbols(params) {
+      return request('GET', `/api/v1/symbols`, params, undefined);
+    },
+    /**
+     * Every strategy in the range, amounts in the reporting currency
+     * @param {{ from: string, to: string, account_id?: string, tags?: string, view?: string }} params
+     * @returns {Promise<import('./api').Strategy[]>}
+     */
+    universe(params) {
+      return request('GET', `/api/v1/universe`, params, undefined);
+    },
+    /**
+     * Saved views
+     * @param {{ owner?: string, endpoint?: string }} [params]
+     * @returns {Promise<import('./api').SavedView[]>}
+     */
+    listViews(params) {
+      return request('GET', `/api/v1/views`, params, undefined);
+    },
+    /**
+     * View saved, admin only
+     * @param {import('./api').SavedViewRequest} body
+     * @returns {Promise<import('./api').SavedView[]>}
+     */
+    createView(body) {
+      return request('POST', `/api/v1/views`, undefined, body);
+    },
+    /**
+     * View removed, admin only
+     * @param {string} id
+     * @returns {Promise<null>}
+     */
+    deleteView(id) {
+      return request('DELETE', `/api/v1/views/${encodeURIComponent(id)}`, undefined, undefined);
+    },
+    /**
+     * The saved view
+     * @param {string} id
+     * @returns {Promise<import('./api').SavedView[]>}
+     */
+    getView(id) {
+      return request('GET', `/api/v1/views/${encodeURIComponent(id)}`, undefined, undefined);
+    },
+    /**
+     * View replaced, admin only
+     * @param {string} id
+     * @param {import('./api').SavedViewRequest} body
+     * @returns {Promise<import('./api').SavedView[]>}
+     */
+    updateView(id, body) {
+      return request('PUT', `/api/v1/views/${encodeURIComponent(id)}`, undefined, body);
+    },
+    /**
+     * Trade counts by period and watermark range
+     * @param {{ from: string, to: string, account_id?: string }} params
+     * @returns {Promise<import('./api').WatermarksBody>}
+     */
+    watermarks(params) {
+      return request('GET', `/api/v1/watermarks`, params, undefined);
+    },
+  };
+})();
@@ -0,0 +1,52 @@
+// Credentials for the API, kept in localStorage so every page shares them.
+// A value containing two dots is treated as a JWT, anything else as an API key
+const CREDENTIAL_KEY = 'dataViewer.credential';
+
+function authHeaders() {
+  const credential = localStorage.getItem(CREDENTIAL_KEY);
+  if (!credential) return {};
+  if (credential.split('.').length === 3) {
+    return { 'Authorization': `Bearer ${credential}` };
+  }
+  return { 'x-api-key': credential };
+}
+
+function promptForCredential() {
+  const credential = window.prompt('Enter your access token or API key');
+  if (credential && credential.trim()) {
+    localStorage.setItem(CREDENTIAL_KEY, credential.trim());
+    return true;
+  }
+  return false;
+}
+
+window.clearCredential = function () {
+  localStorage.removeItem(CREDENTIAL_KEY);
+};
+
+// fetch() with the stored credential, asks for a new one once on 401
+window.apiFetch = async function (url, options = {}) {
+  const send = () => fetch(url, {
+    ...options,
+    headers: { ...(options.headers || {}), ...authHeaders() }
+  });
+
+  let response = await send();
+  if (response.status === 401) {
+    localStorage.removeItem(CREDENTIAL_KEY);
+    if (promptForCredential()) {
+      response = await send();
+    }
+  }
+  return response;
+};
+
+// Attachments need the same credential, so <img> tags can't load /journal/files
+// directly. Returns an object URL to use as the src, revoke it when done
+window.fetchAttachment = async function (path) {
+  const response = await window.apiFetch(`/journal/files/${path}`);
+  if (!response.ok) {
+    throw new Error(`Attachment request failed with status ${response.status}`);
+  }
+  return URL.createObjectURL(await response.blob());
+};
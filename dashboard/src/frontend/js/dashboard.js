@@ -76,51 +76,26 @@ document.addEventListener('DOMContentLoaded', function () {
 
 window.updatePnlChart = async function (start_date, end_date) {
   try {
-    const params = new URLSearchParams();
-    // Use 'from' and 'to' instead of 'start_date' and 'end_date'
-    params.append('from', start_date);
-    params.append('to', end_date);
-    // Add is_active parameter if needed (assuming you want closed positions)
-    params.append('is_active', 'false');
-
-    const url = `/performance?${params.toString()}`;
-    const response = await fetch(url, { method: 'GET' });
-
-    if (!response.ok) {
-      // Try to get error details from response
-      let errorDetails = '';
-      try {
-        const errorText = await response.text();
-        console.log("Error response body:", errorText);
-        errorDetails = errorText;
-      } catch (e) {
-        console.log("Could not extract error details", e);
-        errorDetails = 'Could not extract error details';
-      }
-
-      throw new Error(`HTTP error! Status: ${response.status}, Details: ${errorDetails}`);
-    }
-
-
-    const data = await response.json();
-    console.log("Received data:", data);
+    // Closed positions only, the client throws an ApiError on failure
+    const performance = await api.performance({ from: start_date, to: end_date, is_active: false });
+    console.log("Received data:", performance);
 
     // Check if we have data before updating the chart
-    if (data && data.performance && data.performance.response) {
+    if (Array.isArray(performance)) {
       // Calculate total NET PnL across all strategies (pnl - fee)
-      const totalNetPnl = data.performance.response.reduce((sum, item) => {
+      const totalNetPnl = performance.reduce((sum, item) => {
         const pnl = parseFloat(item.pnl || 0);
         const fee = parseFloat(item.fee || 0);
         return sum + (pnl - fee);
       }, 0);
 
       // Count winning trades
-      const winningTrades = data.performance.response.filter(item =>
+      const winningTrades = performance.filter(item =>
         parseFloat(item.pnl || 0) > 0
       ).length;
 
       // Calculate win rate
-      const totalTrades = data.performance.response.length;
+      const totalTrades = performance.length;
       const winRate = totalTrades > 0 ? (winningTrades / totalTrades * 100) : 0;
 
       // Update dashboard elements
@@ -153,7 +128,7 @@ window.updatePnlChart = async function (start_date, end_date) {
       }
 
       // Calculate total fees
-      const totalFees = data.performance.response.reduce((sum, item) => {
+      const totalFees = performance.reduce((sum, item) => {
         return sum + parseFloat(item.fee || 0);
       }, 0);
 
@@ -171,10 +146,10 @@ window.updatePnlChart = async function (start_date, end_date) {
       }
 
       // Continue with chart update
-      convertToPnLData(data.performance.response, start_date, end_date);
+      convertToPnLData(performance, start_date, end_date);
       return true;
     } else {
-      console.error("Invalid data format received:", data);
+      console.error("Invalid data format received:", performance);
       return false;
     }
   } catch (error) {
@@ -383,16 +358,7 @@ function setText(id, text) {
 
 window.updateMetrics = async function (fromDate, toDate) {
   try {
-    const params = new URLSearchParams();
-    params.append('from', fromDate);
-    params.append('to', toDate);
-    const url = `/metrics?${params.toString()}`;
-    const res = await fetch(url, { method: 'GET' });
-    if (!res.ok) {
-      console.error('Metrics request failed', res.status);
-      return false;
-    }
-    const data = await res.json();
+    const data = await api.metrics({ from: fromDate, to: toDate });
     const metrics = data?.metrics;
     if (!metrics) return false;
 
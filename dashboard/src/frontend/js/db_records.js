@@ -63,7 +63,7 @@ async function fetchStrategyData(symbol = null) {
     console.log(`Fetching data from: ${url}`);
 
     // Make the request with explicit options
-    const response = await fetch(url, {
+    const response = await apiFetch(url, {
       method: 'GET',
       headers: {
         'Accept': 'application/json'
@@ -4,8 +4,7 @@ let watermarkHeatmapChart = null;
 // Function to fetch and update watermark heatmap
 window.updateWatermarkHeatmap = async function(fromDate, toDate) {
     try {
-        const response = await fetch(`/watermarks?from=${fromDate}&to=${toDate}`);
-        const data = await response.json();
+        const data = await api.watermarks({ from: fromDate, to: toDate });
         
         console.log('Watermark data received:', data);
         
@@ -0,0 +1,184 @@
+use std::collections::{BTreeMap, HashMap};
+
+use anyhow::Result;
+use chrono::NaiveDate;
+use common::{db_client::DBClient, settings::SettingsReader};
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use tracing::info;
+
+use crate::calendar::TradingCalendar;
+use crate::models::{settings::FxConfig, strategy::Strategy};
+use crate::service::common::AppError;
+use crate::{schema, telemetry};
+
+#[derive(Debug, Deserialize, sqlx::FromRow)]
+struct FxRate {
+    date: NaiveDate,
+    currency: String,
+    rate: Decimal,
+}
+
+/// Daily conversion rates into the configured reporting currency
+#[derive(Debug, Default)]
+pub(crate) struct FxRates {
+    reporting_currency: String,
+    rates: HashMap<String, BTreeMap<NaiveDate, Decimal>>,
+}
+
+impl FxRates {
+    pub async fn load(config: &FxConfig, db: &DBClient) -> Result<Self> {
+        let rows: Vec<FxRate> = if let Some(path) = &config.rates_file {
+            SettingsReader::read_config_file(path)?
+        } else if let Some(table) = &config.rates_table {
+            let query = format!("SELECT date, currency, rate FROM {}", schema::table_name(table)?);
+            sqlx::query_as::<_, FxRate>(&query).fetch_all(&db.pool).await?
+        } else {
+            Vec::new()
+        };
+
+        let mut rates: HashMap<String, BTreeMap<NaiveDate, Decimal>> = HashMap::new();
+        for row in rows {
+            rates.entry(row.currency.to_uppercase()).or_default().insert(row.date, row.rate);
+        }
+        info!(
+            "Loaded FX rates for {} currencies, reporting in {}",
+            rates.len(),
+            config.reporting_currency
+        );
+
+        Ok(FxRates {
+            reporting_currency: config.reporting_currency.to_uppercase(),
+            rates,
+        })
+    }
+
+    pub fn reporting_currency(&self) -> &str {
+        &self.reporting_currency
+    }
+
+    /// Latest rate on or before `date`, None when the series starts after it
+    pub fn rate(&self, currency: &str, date: NaiveDate) -> Option<Decimal> {
+        if currency.is_empty() || currency.eq_ignore_ascii_case(&self.reporting_currency) {
+            return Some(Decimal::ONE);
+        }
+        let rate = self
+            .rates
+            .get(&currency.to_uppercase())
+            .and_then(|series| series.range(..=date).next_back().map(|(_, rate)| *rate));
+        telemetry::cache_lookup("fx_rates", rate.is_some());
+        rate
+    }
+
+    // Summing an unconverted amount into a reporting currency total would mix currencies
+    fn missing_rate(&self, currency: &str, date: NaiveDate) -> AppError {
+        AppError::ServiceUnavailable(format!(
+            "No {} to {} FX rate on or before {date}",
+            currency.to_uppercase(),
+            self.reporting_currency
+        ))
+    }
+
+    pub fn convert(&self, amount: Decimal, currency: &str, date: NaiveDate) -> Result<Decimal, AppError> {
+        self.rate(currency, date)
+            .map(|rate| amount * rate)
+            .ok_or_else(|| self.missing_rate(currency, date))
+    }
+
+    /// Rewrite P&L, fees and account values of each row into the reporting currency,
+    /// at the rate of the session the trade is booked on
+    pub fn to_reporting(&self, rows: &mut [Strategy], calendar: &TradingCalendar) -> Result<(), AppError> {
+        for s in rows.iter_mut() {
+            let currency = s.account.currency.clone();
+            if currency.is_empty() || currency.eq_ignore_ascii_case(&self.reporting_currency) {
+                continue;
+            }
+            let date = calendar.booking_date(s.exit_time);
+            let rate = self.rate(&currency, date).ok_or_else(|| self.missing_rate(&currency, date))?;
+            s.risk.stats.pnl *= rate;
+            s.risk.stats.fee *= rate;
+            s.account.net_liquidating_value *= rate;
+            s.account.cash_balance *= rate;
+            s.account.cash_flows.deposits *= rate;
+            s.account.cash_flows.fees *= rate;
+            s.account.cash_flows.interest *= rate;
+            s.account.cash_flows.dividends *= rate;
+            s.account.currency = self.reporting_currency.clone();
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 3, day).unwrap()
+    }
+
+    fn rates() -> FxRates {
+        let eur = [(date(10), dec!(1.08)), (date(12), dec!(1.10))].into_iter().collect();
+        FxRates {
+            reporting_currency: "USD".to_string(),
+            rates: [("EUR".to_string(), eur)].into(),
+        }
+    }
+
+    #[test]
+    fn test_rate_carries_forward_but_never_back() {
+        let fx = rates();
+        assert_eq!(fx.rate("eur", date(10)), Some(dec!(1.08)));
+        assert_eq!(fx.rate("EUR", date(11)), Some(dec!(1.08)));
+        assert_eq!(fx.rate("EUR", date(20)), Some(dec!(1.10)));
+        assert_eq!(fx.rate("EUR", date(9)), None);
+        assert_eq!(fx.rate("GBP", date(11)), None);
+        assert_eq!(fx.rate("usd", date(1)), Some(Decimal::ONE));
+    }
+
+    #[test]
+    fn test_convert_fails_without_a_rate() {
+        let fx = rates();
+        assert_eq!(fx.convert(dec!(100), "EUR", date(12)).ok(), Some(dec!(110)));
+        assert_eq!(fx.convert(dec!(100), "", date(9)).ok(), Some(dec!(100)));
+        let missing = fx.convert(dec!(100), "EUR", date(9)).err();
+        assert_eq!(missing.map(|e| e.code()), Some("service_unavailable"));
+    }
+
+    #[test]
+    fn test_to_reporting_rewrites_amounts_and_currency() {
+        let mut strategy = Strategy::fixture();
+        strategy.account.currency = "EUR".to_string();
+        strategy.risk.stats.pnl = dec!(200);
+        strategy.risk.stats.fee = dec!(2);
+        let mut rows = [strategy];
+
+        assert!(rates().to_reporting(&mut rows, &TradingCalendar::default()).is_ok());
+        assert_eq!(rows[0].risk.stats.pnl, dec!(220));
+        assert_eq!(rows[0].risk.stats.fee, dec!(2.2));
+        assert_eq!(rows[0].account.currency, "USD");
+    }
+
+    #[test]
+    fn test_to_reporting_uses_the_booking_date() {
+        // Saturday 01:00 UTC is still Friday's session in New York, so Friday's rate applies
+        let fx = FxRates {
+            reporting_currency: "USD".to_string(),
+            rates: [("EUR".to_string(), [(date(14), dec!(1.08)), (date(15), dec!(1.20))].into_iter().collect())].into(),
+        };
+        let mut strategy = Strategy::fixture();
+        strategy.account.currency = "EUR".to_string();
+        strategy.risk.stats.pnl = dec!(100);
+        strategy.exit_time = "2025-03-15T01:00:00Z".parse().unwrap();
+        let mut rows = [strategy];
+
+        assert!(fx.to_reporting(&mut rows, &TradingCalendar::default()).is_ok());
+        assert_eq!(rows[0].risk.stats.pnl, dec!(108));
+
+        let mut early = [Strategy::fixture()];
+        early[0].account.currency = "EUR".to_string();
+        early[0].exit_time = "2025-03-01T15:00:00Z".parse().unwrap();
+        assert!(fx.to_reporting(&mut early, &TradingCalendar::default()).is_err());
+    }
+}
@@ -0,0 +1,416 @@
+use async_graphql::{
+    ComplexObject, Context, EmptyMutation, EmptySubscription, ErrorExtensions, Object, Schema, SchemaBuilder,
+    dataloader::{DataLoader, Loader},
+    http::GraphiQLSource,
+};
+use axum::{Json, extract::State, response::Html};
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use sqlx::types::Uuid;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::{
+    AppState,
+    models::{
+        account::AccountDailySnapshot,
+        journal::JournalEntry,
+        metrics::{MetricsFilter, MetricsResponseBody},
+        strategy::{Status, Strategy, StrategyType},
+        symbol::{Symbol, SymbolRequest, SymbolSort},
+    },
+    service::{
+        account::fetch_snapshots,
+        common::{AppError, SimpleRequest},
+        extract::{ApiJson, validate_range},
+        journal::fetch_journal,
+        metrics::{build_metrics, fetch_closed},
+        symbols::fetch_symbols,
+        universe::{UniverseFilter, fetch_universe},
+    },
+};
+use crate::telemetry::{self, ObserveQuery};
+
+pub(crate) const GRAPHQL_PATH: &str = "/graphql";
+
+// A strategy -> journal -> strategy round trip is depth 4, leave room for the
+// nested account and risk objects but not for arbitrary cycles
+const MAX_DEPTH: usize = 8;
+const MAX_COMPLEXITY: usize = 500;
+
+pub(crate) type DashboardSchema = Schema<QueryRoot, EmptyMutation, EmptySubscription>;
+
+fn builder() -> SchemaBuilder<QueryRoot, EmptyMutation, EmptySubscription> {
+    Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
+        .limit_depth(MAX_DEPTH)
+        .limit_complexity(MAX_COMPLEXITY)
+}
+
+/// The schema is stateless, the app state and the per request loaders are
+/// attached to each request by the handler
+pub(crate) fn schema() -> DashboardSchema {
+    builder().finish()
+}
+
+// async-graphql already converts anything Display into its error type, so map
+// explicitly to keep the REST error codes and logging
+fn into_error(e: &AppError) -> async_graphql::Error {
+    e.log();
+    async_graphql::Error::new(e.detail()).extend_with(|_, ext| ext.set("code", e.code()))
+}
+
+fn app_state<'a>(ctx: &Context<'a>) -> &'a Arc<AppState> {
+    ctx.data_unchecked::<Arc<AppState>>()
+}
+
+pub(crate) struct StrategyById(Arc<AppState>);
+
+impl Loader<Uuid> for StrategyById {
+    type Value = Strategy;
+    type Error = Arc<AppError>;
+
+    async fn load(&self, keys: &[Uuid]) -> Result<HashMap<Uuid, Strategy>, Self::Error> {
+        let query = r#"
+        SELECT
+            *
+        FROM
+            strategy
+        WHERE
+            local_id = ANY($1)
+        "#;
+
+        let mut rows = sqlx::query_as::<_, Strategy>(query)
+            .bind(keys)
+            .fetch_all(&self.0.db.pool)
+            .observe("strategy_by_id")
+            .await
+            .map_err(|e| Arc::new(AppError::DatabaseError(e)))?;
+        self.0.fx.to_reporting(&mut rows, &self.0.calendar).map_err(Arc::new)?;
+        Ok(rows.into_iter().map(|s| (s.local_id, s)).collect())
+    }
+}
+
+pub(crate) struct JournalById(Arc<AppState>);
+
+impl Loader<Uuid> for JournalById {
+    type Value = JournalEntry;
+    type Error = Arc<AppError>;
+
+    async fn load(&self, keys: &[Uuid]) -> Result<HashMap<Uuid, JournalEntry>, Self::Error> {
+        let query = r#"
+        SELECT
+            *
+        FROM
+            journal
+        WHERE
+            local_id = ANY($1)
+        "#;
+
+        let rows = sqlx::query_as::<_, JournalEntry>(query)
+            .bind(keys)
+            .fetch_all(&self.0.db.pool)
+            .observe("journal_by_id")
+            .await
+            .map_err(|e| Arc::new(AppError::DatabaseError(e)))?;
+        Ok(rows.into_iter().map(|j| (j.local_id, j)).collect())
+    }
+}
+
+/// One traded contract within a range, symbols resolving their strategies
+/// share the filter so a whole symbol list loads in one query per range
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct ContractKey {
+    contract: String,
+    from: NaiveDate,
+    to: NaiveDate,
+    account_id: Option<String>,
+}
+
+type ContractFilter = (NaiveDate, NaiveDate, Option<String>);
+
+// One query per distinct range and account, each covering every contract asking for it
+fn batch_by_filter(keys: &[ContractKey]) -> HashMap<ContractFilter, Vec<String>> {
+    let mut filters: HashMap<ContractFilter, Vec<String>> = HashMap::new();
+    for key in keys {
+        filters
+            .entry((key.from, key.to, key.account_id.clone()))
+            .or_default()
+            .push(key.contract.clone());
+    }
+    filters
+}
+
+pub(crate) struct StrategiesByContract(Arc<AppState>);
+
+impl Loader<ContractKey> for StrategiesByContract {
+    type Value = Vec<Strategy>;
+    type Error = Arc<AppError>;
+
+    async fn load(&self, keys: &[ContractKey]) -> Result<HashMap<ContractKey, Vec<Strategy>>, Self::Error> {
+        let query = r#"
+        SELECT
+            *
+        FROM
+            strategy
+        WHERE
+            symbol = ANY($1)
+        AND (entry_time AT TIME ZONE $5)::date >= $2
+        AND (exit_time AT TIME ZONE $5)::date <= $3
+        AND ($4::text IS NULL OR account->>'account_id' = $4)
+        ORDER BY
+            entry_time
+        "#;
+
+        let mut loaded: HashMap<ContractKey, Vec<Strategy>> =
+            keys.iter().map(|key| (key.clone(), Vec::new())).collect();
+        for ((from, to, account_id), contracts) in batch_by_filter(keys) {
+            let mut rows = sqlx::query_as::<_, Strategy>(query)
+                .bind(&contracts)
+                .bind(from)
+                .bind(to)
+                .bind(&account_id)
+                .bind(self.0.calendar.timezone_name())
+                .fetch_all(&self.0.db.pool)
+                .observe("strategies_by_contract")
+                .await
+                .map_err(|e| Arc::new(AppError::DatabaseError(e)))?;
+            self.0.fx.to_reporting(&mut rows, &self.0.calendar).map_err(Arc::new)?;
+            for strategy in rows {
+                let key = ContractKey { contract: strategy.contract.clone(), from, to, account_id: account_id.clone() };
+                loaded.entry(key).or_default().push(strategy);
+            }
+        }
+        Ok(loaded)
+    }
+}
+
+#[ComplexObject]
+impl Strategy {
+    /// Journal entry written against this strategy
+    async fn journal(&self, ctx: &Context<'_>) -> async_graphql::Result<Option<JournalEntry>> {
+        ctx.data_unchecked::<DataLoader<JournalById>>()
+            .load_one(self.local_id)
+            .await
+            .map_err(|e| into_error(&e))
+    }
+
+    /// Realised P&L net of fees, in the reporting currency
+    async fn net_pnl(&self) -> Decimal {
+        self.risk.stats.pnl - self.risk.stats.fee
+    }
+}
+
+#[ComplexObject]
+impl Symbol {
+    /// Contracts traded under this root as reported by the broker
+    async fn contracts(&self) -> Vec<String> {
+        self.contracts.iter().map(|c| c.raw.clone()).collect()
+    }
+
+    /// Strategies on any of the root's contracts entered and exited within the range
+    async fn strategies(
+        &self,
+        ctx: &Context<'_>,
+        from: NaiveDate,
+        to: NaiveDate,
+        account_id: Option<String>,
+    ) -> async_graphql::Result<Vec<Strategy>> {
+        validate_range(from, to, &app_state(ctx).settings.api).map_err(|e| into_error(&e))?;
+
+        let keys = self.contracts.iter().map(|c| ContractKey {
+            contract: c.raw.clone(),
+            from,
+            to,
+            account_id: account_id.clone(),
+        });
+        let loaded = ctx
+            .data_unchecked::<DataLoader<StrategiesByContract>>()
+            .load_many(keys)
+            .await
+            .map_err(|e| into_error(&e))?;
+
+        let mut strategies: Vec<Strategy> = loaded.into_values().flatten().collect();
+        strategies.sort_by_key(|s| s.entry_time);
+        Ok(strategies)
+    }
+}
+
+#[ComplexObject]
+impl JournalEntry {
+    /// Strategy the entry was written against
+    async fn strategy(&self, ctx: &Context<'_>) -> async_graphql::Result<Option<Strategy>> {
+        ctx.data_unchecked::<DataLoader<StrategyById>>()
+            .load_one(self.local_id)
+            .await
+            .map_err(|e| into_error(&e))
+    }
+}
+
+pub(crate) struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    /// Traded roots with their contracts and activity, P&L in the reporting currency
+    async fn symbols(
+        &self,
+        ctx: &Context<'_>,
+        account_id: Option<String>,
+        prefix: Option<String>,
+        #[graphql(default)] sort: SymbolSort,
+    ) -> async_graphql::Result<Vec<Symbol>> {
+        let request = SymbolRequest { account_id, prefix, sort };
+        fetch_symbols(app_state(ctx), &request).await.map_err(|e| into_error(&e))
+    }
+
+    /// Strategies entered and exited within the range, tags match any of the given ones
+    #[allow(clippy::too_many_arguments)]
+    async fn strategies(
+        &self,
+        ctx: &Context<'_>,
+        from: NaiveDate,
+        to: NaiveDate,
+        account_id: Option<String>,
+        tags: Option<Vec<String>>,
+        symbol: Option<String>,
+        status: Option<Status>,
+        strategy_type: Option<StrategyType>,
+    ) -> async_graphql::Result<Vec<Strategy>> {
+        let state = app_state(ctx);
+        validate_range(from, to, &state.settings.api).map_err(|e| into_error(&e))?;
+
+        let request = SimpleRequest { from, to, account_id, tags: tags.map(|t| t.join(",")) };
+        let filter = UniverseFilter { symbol, status, strategy_type };
+        fetch_universe(state, &request, &filter).await.map_err(|e| into_error(&e))
+    }
+
+    async fn strategy(&self, ctx: &Context<'_>, local_id: Uuid) -> async_graphql::Result<Option<Strategy>> {
+        ctx.data_unchecked::<DataLoader<StrategyById>>()
+            .load_one(local_id)
+            .await
+            .map_err(|e| into_error(&e))
+    }
+
+    /// Journal entries tagged with any of the given tags, newest first
+    async fn journal(&self, ctx: &Context<'_>, tags: Option<Vec<String>>) -> async_graphql::Result<Vec<JournalEntry>> {
+        fetch_journal(app_state(ctx), &tags.map(|t| t.join(","))).await.map_err(|e| into_error(&e))
+    }
+
+    /// Risk-adjusted metrics over closed strategies in the reporting currency
+    async fn metrics(
+        &self,
+        ctx: &Context<'_>,
+        from: NaiveDate,
+        to: NaiveDate,
+        account_id: Option<String>,
+        tags: Option<Vec<String>>,
+        strategy_type: Option<StrategyType>,
+    ) -> async_graphql::Result<MetricsResponseBody> {
+        let state = app_state(ctx);
+        validate_range(from, to, &state.settings.api).map_err(|e| into_error(&e))?;
+
+        let filter = MetricsFilter { from, to, account_id, tags: tags.map(|t| t.join(",")), strategy_type };
+        let rows = fetch_closed(state, &filter).await.map_err(|e| into_error(&e))?;
+        Ok(build_metrics(from, to, state.fx.reporting_currency(), &state.calendar, &rows))
+    }
+
+    /// Latest daily snapshot per account, ordered by account then date
+    async fn account_snapshots(
+        &self,
+        ctx: &Context<'_>,
+        from: NaiveDate,
+        to: NaiveDate,
+        account_id: Option<String>,
+    ) -> async_graphql::Result<Vec<AccountDailySnapshot>> {
+        let state = app_state(ctx);
+        validate_range(from, to, &state.settings.api).map_err(|e| into_error(&e))?;
+
+        let snapshots = fetch_snapshots(state, from, to, account_id.as_deref()).await.map_err(|e| into_error(&e))?;
+        Ok(snapshots.into_values().flatten().collect())
+    }
+
+}
+
+pub(crate) async fn graphql(
+    State(state): State<Arc<AppState>>,
+    ApiJson(request): ApiJson<async_graphql::Request>,
+) -> Json<async_graphql::Response> {
+    // Loaders live for a single request so batches never mix callers or serve stale rows
+    let request = request
+        .data(DataLoader::new(StrategyById(state.clone()), telemetry::spawner()))
+        .data(DataLoader::new(JournalById(state.clone()), telemetry::spawner()))
+        .data(DataLoader::new(StrategiesByContract(state.clone()), telemetry::spawner()))
+        .data(state.clone());
+    Json(state.graphql.execute(request).await)
+}
+
+pub(crate) async fn graphiql() -> Html<String> {
+    Html(GraphiQLSource::build().endpoint(GRAPHQL_PATH).finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_schema_exposes_batched_relations() {
+        let sdl = builder().finish().sdl();
+
+        for expected in [
+            "type Strategy",
+            "journal: JournalEntry",
+            "netPnl: Decimal!",
+            "strategy: Strategy",
+            "contracts: [String!]!",
+            "strategies(from: NaiveDate!, to: NaiveDate!, accountId: String): [Strategy!]!",
+            "metrics(",
+            "accountSnapshots(",
+        ] {
+            assert!(sdl.contains(expected), "missing {expected:?} in\n{sdl}");
+        }
+    }
+
+    type Queries = Arc<std::sync::Mutex<Vec<(ContractFilter, Vec<String>)>>>;
+
+    // Stands in for StrategiesByContract, recording the queries it would run
+    struct RecordingLoader(Queries);
+
+    impl Loader<ContractKey> for RecordingLoader {
+        type Value = Vec<Strategy>;
+        type Error = Arc<AppError>;
+
+        async fn load(&self, keys: &[ContractKey]) -> Result<HashMap<ContractKey, Vec<Strategy>>, Self::Error> {
+            self.0.lock().unwrap().extend(batch_by_filter(keys));
+            Ok(HashMap::new())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_symbols_batch_into_one_query_per_filter() {
+        let queries = Queries::default();
+        let loader = DataLoader::new(RecordingLoader(queries.clone()), tokio::spawn);
+        let (from, to) = (NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(), NaiveDate::from_ymd_opt(2025, 3, 31).unwrap());
+        let keys = |contracts: &[&str], account_id: Option<&str>| -> Vec<ContractKey> {
+            contracts
+                .iter()
+                .map(|c| ContractKey { contract: c.to_string(), from, to, account_id: account_id.map(str::to_string) })
+                .collect()
+        };
+
+        // Three symbols resolving strategies in the same request, one for another account
+        let (es, spy, other) = tokio::join!(
+            loader.load_many(keys(&["/ESH5", "/ESM5"], None)),
+            loader.load_many(keys(&["SPY"], None)),
+            loader.load_many(keys(&["SPY"], Some("acct-2"))),
+        );
+        assert!(es.is_ok() && spy.is_ok() && other.is_ok());
+
+        let mut queries = queries.lock().unwrap().clone();
+        queries.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(queries.len(), 2);
+        let (filter, mut contracts) = queries.remove(0);
+        contracts.sort();
+        assert_eq!(filter, (from, to, None));
+        assert_eq!(contracts, ["/ESH5", "/ESM5", "SPY"]);
+        assert_eq!(queries[0], ((from, to, Some("acct-2".to_string())), vec!["SPY".to_string()]));
+    }
+}
@@ -1,20 +1,42 @@
 use axum::{
     Router,
-    routing::get,
+    http::{HeaderName, HeaderValue, Method, header},
+    middleware,
+    routing::{get, post},
 };
 use clap::Parser;
-use common::{aws_logging, db_client::{self, DBClient}, load_settings_from_s3, settings::SettingsReader};
-use models::settings::Settings;
+use auth::Authenticator;
+use calendar::TradingCalendar;
+use benchmark::BenchmarkSeries;
+use fx::FxRates;
+use metrics_exporter_prometheus::PrometheusHandle;
+use common::{aws_logging::{self, LogShipper}, db_client::{self, DBClient}, load_settings_from_s3, settings::SettingsReader};
+use service::envelope;
+use models::{audit::AuditRecord, event::StrategyEvent, settings::Settings};
 use serde_json::to_string;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::signal;
+use tokio::sync::{broadcast, mpsc};
 use tokio_util::sync::CancellationToken;
-use tracing::info;
-use tower_http::cors::{Any, CorsLayer};
+use tracing::{error, info, warn};
+use tower_http::cors::{AllowOrigin, CorsLayer};
 use tower_http::services::ServeDir;
 
+mod auth;
+mod benchmark;
+mod calendar;
+// Rendered from the spec by the openapi drift test
+#[cfg(test)]
+mod client;
+mod fx;
+mod graphql;
 mod models;
+mod openapi;
+mod schema;
 mod service;
+mod symbology;
+mod telemetry;
 
 const S3_STORED_SETTINGS: &str = "settings.json";
 
@@ -28,27 +50,58 @@ struct Args {
     frontend: Option<String>,
 }
 
-fn graceful_shutdown(is_graceful_shutdown: &mut bool, shutdown_signal: &CancellationToken) {
-    *is_graceful_shutdown = true;
-    info!("Graceful shutdown initiated");
-    shutdown_signal.cancel();
+// Cancels the shutdown token on the first SIGTERM or Ctrl-C
+async fn wait_for_signal(mut sigterm: signal::unix::Signal, shutdown: CancellationToken) {
+    tokio::select! {
+        _ = sigterm.recv() => info!("Received SIGTERM"),
+        _ = signal::ctrl_c() => info!("Received Ctrl-C"),
+    }
+    info!("Graceful shutdown initiated, failing readiness");
+    shutdown.cancel();
 }
 
 struct AppState {
     db: DBClient,
+    events: broadcast::Sender<StrategyEvent>,
+    settings: Settings,
+    fx: FxRates,
+    // None when no benchmark source is configured
+    benchmark: Option<BenchmarkSeries>,
+    calendar: TradingCalendar,
+    auth: Authenticator,
+    // None when auditing is disabled
+    audit: Option<mpsc::Sender<AuditRecord>>,
+    graphql: graphql::DashboardSchema,
+    // Cancelled on SIGTERM, readiness fails and streams close while requests drain
+    shutdown: CancellationToken,
+    // Where the settings were read from, reported by /health/details
+    config_source: String,
+    // None when logs only go to stdout
+    logs: Option<LogShipper>,
+    // None when Prometheus exposition is disabled
+    prometheus: Option<PrometheusHandle>,
+}
+
+// Innermost first: the timeout bounds the handler, authentication runs outside it so
+// timed out responses still name the caller, and audit sees every outcome
+fn protect(state: &Arc<AppState>, router: Router<Arc<AppState>>) -> Router<Arc<AppState>> {
+    router
+        .route_layer(middleware::from_fn_with_state(state.clone(), service::extract::request_timeout))
+        .route_layer(middleware::from_fn_with_state(state.clone(), auth::authenticate))
+        .route_layer(middleware::from_fn_with_state(state.clone(), service::audit::audit))
 }
 
 #[tokio::main]
 async fn main() {
-    let mut sigterm = signal::unix::signal(signal::unix::SignalKind::terminate()).unwrap();
+    let sigterm = signal::unix::signal(signal::unix::SignalKind::terminate()).unwrap();
 
     let cmdline_args = Args::parse();
-    let settings = match cmdline_args.settings {
+    let (settings, config_source) = match cmdline_args.settings {
         Some(settings) => {
-           SettingsReader::read_config_file::<Settings>(&settings).unwrap() 
+           (SettingsReader::read_config_file::<Settings>(&settings).unwrap(), format!("file:{settings}"))
         },
         None => {
-            load_settings_from_s3::<Settings>(S3_STORED_SETTINGS).await
+            (load_settings_from_s3::<Settings>(S3_STORED_SETTINGS).await, format!("s3:{S3_STORED_SETTINGS}"))
         }
     };
     
@@ -57,10 +110,23 @@ async fn main() {
         None => "frontend".to_string(),
     };
 
+    // Background workers stop on cancel_token, which only fires once the server has drained
     let cancel_token = CancellationToken::new();
-    if let Err(_e) = aws_logging::init_cloudwatch_logger(&settings.logging) {
-        tracing_subscriber::fmt::init();
-    }
+    let shutdown = CancellationToken::new();
+    // Spans reach the collector either way, CloudWatch flushes them with the logs
+    let (logs, traces) = match aws_logging::init_cloudwatch_logger(&settings.logging) {
+        Ok(shipper) => (Some(shipper), None),
+        Err(_e) => (None, aws_logging::init_stdout_logger(&settings.logging)),
+    };
+
+    let prometheus = match settings.prometheus.enabled.then(telemetry::install) {
+        Some(Ok(handle)) => Some(handle),
+        Some(Err(e)) => {
+            warn!("Failed to install the Prometheus recorder, metrics are disabled: {e:#}");
+            None
+        }
+        None => None,
+    };
 
     let version = env!("CARGO_PKG_VERSION");
 
@@ -71,24 +137,140 @@ async fn main() {
         &to_string(&settings).expect("Failed to parse settings to json")
     );
 
+    symbology::init(&settings.symbols);
+
     let db = db_client::startup_db(&settings.database).await;
 
-    let state = Arc::new(AppState { db });
+    if let Err(e) = schema::ensure(&db).await {
+        error!("Failed to prepare database schema: {e:#}");
+        std::process::exit(1);
+    }
+
+    let fx = match FxRates::load(&settings.fx, &db).await {
+        Ok(fx) => fx,
+        Err(e) => {
+            error!("Failed to load FX rates: {e:#}");
+            std::process::exit(1);
+        }
+    };
+
+    let benchmark = match BenchmarkSeries::load(&settings.benchmark, &db).await {
+        Ok(benchmark) => benchmark,
+        Err(e) => {
+            error!("Failed to load benchmark closes: {e:#}");
+            std::process::exit(1);
+        }
+    };
+
+    let calendar = match TradingCalendar::load(&settings.calendar) {
+        Ok(calendar) => calendar,
+        Err(e) => {
+            error!("Failed to load trading calendar: {e:#}");
+            std::process::exit(1);
+        }
+    };
+
+    let auth = match Authenticator::load(&settings.auth).await {
+        Ok(auth) => auth,
+        Err(e) => {
+            error!("Failed to initialise authentication: {e:#}");
+            std::process::exit(1);
+        }
+    };
+
+    let (events, _) = broadcast::channel(service::events::EVENT_BUFFER);
+
+    let (audit, audit_receiver) = mpsc::channel(settings.audit.buffer.max(1));
+    let audit = settings.audit.enabled.then_some(audit);
 
+    let state = Arc::new(AppState {
+        db,
+        events,
+        settings,
+        fx,
+        benchmark,
+        calendar,
+        auth,
+        audit,
+        graphql: graphql::schema(),
+        shutdown: shutdown.clone(),
+        config_source,
+        logs,
+        prometheus,
+    });
+
+    tokio::spawn(service::events::listen_strategy_events(
+        state.clone(),
+        cancel_token.clone(),
+    ));
+    let audit_writer = state.audit.is_some().then(|| {
+        tokio::spawn(service::audit::write_audit_log(
+            state.clone(),
+            audit_receiver,
+            cancel_token.clone(),
+        ))
+    });
+
+    // A dropped origin would quietly block that frontend, so refuse to start instead
+    let origins: Vec<HeaderValue> = match state
+        .settings
+        .cors
+        .allowed_origins
+        .iter()
+        .map(|origin| HeaderValue::from_str(origin).map_err(|e| format!("{origin:?}: {e}")))
+        .collect()
+    {
+        Ok(origins) => origins,
+        Err(e) => {
+            error!("Invalid CORS origin {e}");
+            std::process::exit(1);
+        }
+    };
     let cors = CorsLayer::new()
-        .allow_origin(Any)
-        .allow_methods(Any)
-        .allow_headers(Any);
+        .allow_origin(AllowOrigin::list(origins))
+        .allow_methods([Method::GET, Method::POST, Method::PUT, Method::DELETE])
+        .allow_headers([header::AUTHORIZATION, header::CONTENT_TYPE, HeaderName::from_static(auth::API_KEY_HEADER)]);
+
+    let attachments = ServeDir::new(&state.settings.journal.attachments_dir);
+    let max_attachment_bytes = state.settings.journal.max_attachment_bytes;
+
+    let api = openapi::routes(max_attachment_bytes);
+    let timeout = middleware::from_fn_with_state(state.clone(), service::extract::request_timeout);
+
+    let protect = |router| protect(&state, router);
+    let routes = protect(api.protected);
+    // Raw files, kept out of the /api/v1 envelope and served as downloads only
+    let attachment_routes = protect(
+        Router::new()
+            .nest_service("/journal/files", attachments)
+            .layer(middleware::from_fn(service::journal::attachment_headers)),
+    );
+    // GraphQL carries its own data/errors shape, so it stays out of the envelope and the spec
+    let graphql_routes = protect(Router::new().route(graphql::GRAPHQL_PATH, post(graphql::graphql)));
+
+    // Scraped through API auth unless it has a listener of its own
+    let scrape = Router::new().route(&state.settings.prometheus.path, get(telemetry::scrape));
+    let scrape_routes = match (&state.prometheus, state.settings.prometheus.port) {
+        (Some(_), None) => scrape
+            .clone()
+            .route_layer(middleware::from_fn_with_state(state.clone(), auth::authenticate))
+            .route_layer(timeout.clone()),
+        _ => Router::new(),
+    };
 
     let app = Router::new()
-        .route("/health", get(service::health::health))
-        .route("/symbols", get(service::symbols::symbols))
-        .route("/strategy/{symbol}", get(service::strategy::strategy))
-        .route("/universe", get(service::universe::universe))
-        .route("/performance", get(service::performance::performance))
-        .route("/metrics", get(service::metrics::metrics))
-        .route("/watermarks", get(service::watermarks::watermarks))
-        .with_state(state)
+        .nest(
+            envelope::V1_PREFIX,
+            routes.clone().layer(middleware::from_fn_with_state(Arc::new(api.unwrap), envelope::envelope)),
+        )
+        .merge(routes.layer(middleware::from_fn(envelope::deprecated)))
+        .merge(attachment_routes)
+        .merge(graphql_routes)
+        .merge(api.public.route("/graphiql", get(graphql::graphiql)).route_layer(timeout))
+        .merge(openapi::docs(api.spec))
+        .merge(scrape_routes)
+        .route_layer(middleware::from_fn(telemetry::track_requests))
+        .with_state(state.clone())
         .layer(cors)
         .fallback_service(ServeDir::new(frontend_path).append_index_html_on_directories(true));
 
@@ -97,22 +279,135 @@ async fn main() {
     let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
 
     info!("listening on {}", listener.local_addr().unwrap());
-    axum::serve(listener, app).await.unwrap();
-
-    let mut is_graceful_shutdown = false;
-    tokio::spawn(async move {
-        loop {
-            tokio::select! {
-                _ = cancel_token.cancelled() => {
-                    break;
-                }
-                _ = sigterm.recv() => {
-                    graceful_shutdown(&mut is_graceful_shutdown, &cancel_token);
-                }
-                _ = signal::ctrl_c() => {
-                    graceful_shutdown(&mut is_graceful_shutdown, &cancel_token);
-                }
+
+    if let (Some(_), Some(port)) = (&state.prometheus, state.settings.prometheus.port) {
+        let addr = std::net::SocketAddr::from(([0, 0, 0, 0], port));
+        match tokio::net::TcpListener::bind(addr).await {
+            Ok(listener) => {
+                info!("Serving Prometheus metrics on {addr}{}", state.settings.prometheus.path);
+                let stop = shutdown.clone();
+                let scrape = scrape.with_state(state.clone());
+                tokio::spawn(async move {
+                    let server = axum::serve(listener, scrape).with_graceful_shutdown(stop.cancelled_owned());
+                    if let Err(e) = server.await {
+                        error!("Prometheus listener error: {e}");
+                    }
+                });
             }
+            Err(e) => error!("Failed to bind the Prometheus listener on {addr}: {e}"),
         }
+    }
+    tokio::spawn(wait_for_signal(sigterm, shutdown.clone()));
+
+    let config = state.settings.shutdown.clone();
+    let readiness_delay = Duration::from_secs(config.readiness_delay_secs);
+    let drain_timeout = Duration::from_secs(config.drain_timeout_secs);
+    let flush_timeout = Duration::from_secs(config.flush_timeout_secs);
+
+    // Keep accepting while readiness reports unhealthy, then close the listener and drain
+    let stop_accepting = shutdown.clone();
+    let server = axum::serve(listener, app).with_graceful_shutdown(async move {
+        stop_accepting.cancelled().await;
+        tokio::time::sleep(readiness_delay).await;
+        info!("Closing listener, draining in-flight requests for up to {drain_timeout:?}");
     });
+    let drain_deadline = async {
+        shutdown.cancelled().await;
+        tokio::time::sleep(readiness_delay + drain_timeout).await;
+    };
+
+    tokio::select! {
+        result = server => {
+            if let Err(e) = result {
+                error!("Server error: {e}");
+            }
+        }
+        _ = drain_deadline => warn!("Drain timed out, dropping remaining connections"),
+    }
+
+    cancel_token.cancel();
+    if let Some(writer) = audit_writer
+        && tokio::time::timeout(flush_timeout, writer).await.is_err()
+    {
+        warn!("Timed out flushing audit records");
+    }
+    // Connections abandoned by a timed out drain may still hold pool connections
+    if tokio::time::timeout(flush_timeout, state.db.close()).await.is_err() {
+        warn!("Timed out closing the database pool");
+    }
+    info!("Shutdown complete");
+
+    if let Some(traces) = &traces
+        && !traces.flush(flush_timeout).await
+    {
+        warn!("Timed out flushing spans");
+    }
+
+    if let Some(logs) = &state.logs
+        && !logs.flush(flush_timeout).await
+    {
+        warn!("Timed out flushing CloudWatch logs");
+    }
+}
+
+#[cfg(test)]
+impl AppState {
+    // Auth disabled and a pool that never connects, for middleware and handlers
+    // that do not reach the database. `settings` overrides top level sections
+    pub(crate) fn for_tests(settings: serde_json::Value) -> (Arc<Self>, mpsc::Receiver<AuditRecord>) {
+        let mut merged = serde_json::json!({
+            "database": {"name": "test", "port": 5432, "host": "localhost", "user": "test"},
+            "logging": {"log_group": "test", "log_stream": "test", "level": "info"},
+            "auth": {"enabled": false},
+        });
+        if let (Some(base), serde_json::Value::Object(overrides)) = (merged.as_object_mut(), settings) {
+            base.extend(overrides);
+        }
+        let settings: Settings = serde_json::from_value(merged).unwrap();
+        let pool = sqlx::postgres::PgPoolOptions::new().connect_lazy("postgres://test@localhost/test").unwrap();
+        let (audit, audit_receiver) = mpsc::channel(16);
+        let (events, _) = broadcast::channel(service::events::EVENT_BUFFER);
+
+        let state = AppState {
+            db: DBClient { pool },
+            events,
+            fx: FxRates::default(),
+            benchmark: None,
+            calendar: TradingCalendar::default(),
+            auth: Authenticator::disabled(&settings.auth),
+            audit: settings.audit.enabled.then_some(audit),
+            graphql: graphql::schema(),
+            shutdown: CancellationToken::new(),
+            config_source: "test".to_string(),
+            logs: None,
+            prometheus: None,
+            settings,
+        };
+        (Arc::new(state), audit_receiver)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, http::{Request, StatusCode}};
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn test_timed_out_requests_are_audited() {
+        let (state, mut audit) = AppState::for_tests(serde_json::json!({"api": {"request_timeout_secs": 0}}));
+        let router = Router::new().route("/slow", get(std::future::pending::<&'static str>));
+        let app = protect(&state, router).with_state(state);
+
+        let response = app
+            .oneshot(Request::get("/slow?from=2025-01-01").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::GATEWAY_TIMEOUT);
+
+        let record = audit.try_recv().unwrap();
+        assert_eq!(record.route, "/slow");
+        assert_eq!(record.status, 504);
+        assert_eq!(record.subject.as_deref(), Some("anonymous"));
+    }
 }
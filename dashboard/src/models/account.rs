@@ -1,8 +1,14 @@
+use axum::{Json, http::StatusCode, response::IntoResponse};
 use chrono::NaiveDate;
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
+use serde_json::json;
+use async_graphql::SimpleObject;
+use utoipa::ToSchema;
 
-#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+use crate::calendar::TradingCalendar;
+
+#[derive(Default, Debug, Clone, Serialize, Deserialize, ToSchema, SimpleObject)]
 #[serde(default)]
 pub(crate) struct AccountDailySnapshot {
     pub account_id: String,
@@ -14,7 +20,7 @@ pub(crate) struct AccountDailySnapshot {
     pub risk_free_annual: f64,
 }
 
-#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+#[derive(Default, Debug, Clone, Serialize, Deserialize, ToSchema, SimpleObject)]
 #[serde(default)]
 pub(crate) struct AccountCashFlows {
     pub deposits: Decimal,
@@ -22,3 +28,218 @@ pub(crate) struct AccountCashFlows {
     pub interest: Decimal,
     pub dividends: Decimal,
 }
+
+// Where the day-over-day change in NLV came from. `trading` is the residual once
+// external flows are removed, so the fields always sum back to `nlv_change`:
+// nlv_change = trading + deposits + interest + dividends - fees
+#[derive(Default, Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub(crate) struct CashFlowAttribution {
+    pub nlv_change: Decimal,
+    pub trading: Decimal,
+    pub deposits: Decimal,
+    pub interest: Decimal,
+    pub dividends: Decimal,
+    pub fees: Decimal,
+}
+
+impl CashFlowAttribution {
+    // Only `curr` knows the flows since `prev`, so a trading day without a snapshot
+    // in between would book its deposits and fees as trading. None in that case
+    pub fn between(
+        prev: &AccountDailySnapshot,
+        curr: &AccountDailySnapshot,
+        calendar: &TradingCalendar,
+    ) -> Option<Self> {
+        if !missing_trading_days(prev.date, curr.date, calendar).is_empty() {
+            return None;
+        }
+        let flows = &curr.cash_flows;
+        let nlv_change = curr.net_liquidating_value - prev.net_liquidating_value;
+        Some(CashFlowAttribution {
+            nlv_change,
+            trading: nlv_change - flows.deposits - flows.interest - flows.dividends + flows.fees,
+            deposits: flows.deposits,
+            interest: flows.interest,
+            dividends: flows.dividends,
+            fees: flows.fees,
+        })
+    }
+
+    pub fn accumulate(&mut self, other: &CashFlowAttribution) {
+        self.nlv_change += other.nlv_change;
+        self.trading += other.trading;
+        self.deposits += other.deposits;
+        self.interest += other.interest;
+        self.dividends += other.dividends;
+        self.fees += other.fees;
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub(crate) struct AccountDay {
+    pub date: NaiveDate,
+    pub net_liquidating_value: Decimal,
+    pub cash_balance: Decimal,
+    pub risk_free_annual: f64,
+    // Absent on the first day of the range and after a trading day without a snapshot,
+    // the flows in between are unknown
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attribution: Option<CashFlowAttribution>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub(crate) struct AccountSeries {
+    pub account_id: String,
+    pub currency: String,
+    pub start_nlv: Decimal,
+    pub end_nlv: Decimal,
+    // Sum of the attributed days only, see `missing_days`
+    pub totals: CashFlowAttribution,
+    pub days: Vec<AccountDay>,
+    // Trading days inside the range without a snapshot
+    pub missing_days: Vec<NaiveDate>,
+}
+
+// Trading days strictly between two snapshot dates
+fn missing_trading_days(prev: NaiveDate, curr: NaiveDate, calendar: &TradingCalendar) -> Vec<NaiveDate> {
+    match (prev.succ_opt(), curr.pred_opt()) {
+        (Some(from), Some(to)) if from <= to => calendar.trading_days(from, to),
+        _ => Vec::new(),
+    }
+}
+
+impl AccountSeries {
+    // Expects snapshots for a single account sorted by date without duplicates
+    pub fn from_snapshots(snapshots: &[AccountDailySnapshot], calendar: &TradingCalendar) -> Option<Self> {
+        let first = snapshots.first()?;
+        let last = snapshots.last()?;
+        let mut totals = CashFlowAttribution::default();
+        let mut days = Vec::with_capacity(snapshots.len());
+        let mut missing_days = Vec::new();
+        let mut prev: Option<&AccountDailySnapshot> = None;
+
+        for snap in snapshots {
+            if let Some(p) = prev {
+                missing_days.extend(missing_trading_days(p.date, snap.date, calendar));
+            }
+            let attribution = prev.and_then(|p| CashFlowAttribution::between(p, snap, calendar));
+            if let Some(a) = &attribution {
+                totals.accumulate(a);
+            }
+            days.push(AccountDay {
+                date: snap.date,
+                net_liquidating_value: snap.net_liquidating_value,
+                cash_balance: snap.cash_balance,
+                risk_free_annual: snap.risk_free_annual,
+                attribution,
+            });
+            prev = Some(snap);
+        }
+
+        Some(AccountSeries {
+            account_id: first.account_id.clone(),
+            currency: first.currency.clone(),
+            start_nlv: first.net_liquidating_value,
+            end_nlv: last.net_liquidating_value,
+            totals,
+            days,
+            missing_days,
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub(crate) struct AccountResponse {
+    pub response: Vec<AccountSeries>,
+}
+
+impl IntoResponse for AccountResponse {
+    fn into_response(self) -> axum::response::Response {
+        let body = Json(json!({
+            "account": self
+        }));
+
+        (StatusCode::OK, body).into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn snapshot(day: u32, nlv: Decimal, cash_flows: AccountCashFlows) -> AccountDailySnapshot {
+        AccountDailySnapshot {
+            account_id: "ACC-1".to_string(),
+            date: NaiveDate::from_ymd_opt(2025, 3, day).unwrap(),
+            currency: "USD".to_string(),
+            net_liquidating_value: nlv,
+            cash_flows,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_attribution_removes_external_flows_from_trading() {
+        let prev = snapshot(10, dec!(10000), AccountCashFlows::default());
+        let flows = AccountCashFlows {
+            deposits: dec!(1000),
+            fees: dec!(15),
+            interest: dec!(5),
+            dividends: dec!(10),
+        };
+        let curr = snapshot(11, dec!(11200), flows);
+
+        let attribution = CashFlowAttribution::between(&prev, &curr, &TradingCalendar::default()).unwrap();
+        assert_eq!(attribution.nlv_change, dec!(1200));
+        assert_eq!(attribution.trading, dec!(200));
+        let rebuilt = attribution.trading + attribution.deposits + attribution.interest + attribution.dividends
+            - attribution.fees;
+        assert_eq!(rebuilt, attribution.nlv_change);
+    }
+
+    #[test]
+    fn test_series_skips_first_day_and_totals_the_rest() {
+        let deposit = AccountCashFlows { deposits: dec!(500), ..Default::default() };
+        let snapshots = vec![
+            snapshot(10, dec!(10000), AccountCashFlows::default()),
+            snapshot(11, dec!(10600), deposit),
+            snapshot(12, dec!(10400), AccountCashFlows::default()),
+        ];
+
+        let series = AccountSeries::from_snapshots(&snapshots, &TradingCalendar::default()).unwrap();
+        assert_eq!(series.account_id, "ACC-1");
+        assert_eq!((series.start_nlv, series.end_nlv), (dec!(10000), dec!(10400)));
+        assert!(series.days[0].attribution.is_none());
+        assert_eq!(series.days.len(), 3);
+        assert_eq!(series.totals.nlv_change, dec!(400));
+        assert_eq!(series.totals.deposits, dec!(500));
+        assert_eq!(series.totals.trading, dec!(-100));
+    }
+
+    #[test]
+    fn test_series_needs_a_snapshot() {
+        assert!(AccountSeries::from_snapshots(&[], &TradingCalendar::default()).is_none());
+    }
+
+    #[test]
+    fn test_series_leaves_gaps_unattributed() {
+        let fee = AccountCashFlows { fees: dec!(20), ..Default::default() };
+        // Friday 7th to Monday 10th spans only a weekend, the 11th and 12th are missing
+        let snapshots = vec![
+            snapshot(7, dec!(10000), AccountCashFlows::default()),
+            snapshot(10, dec!(10100), fee.clone()),
+            snapshot(13, dec!(9000), fee),
+        ];
+
+        let series = AccountSeries::from_snapshots(&snapshots, &TradingCalendar::default()).unwrap();
+        assert_eq!(series.days[1].attribution.as_ref().map(|a| a.trading), Some(dec!(120)));
+        assert!(series.days[2].attribution.is_none());
+        assert_eq!(
+            series.missing_days,
+            [NaiveDate::from_ymd_opt(2025, 3, 11).unwrap(), NaiveDate::from_ymd_opt(2025, 3, 12).unwrap()]
+        );
+        assert_eq!(series.totals.nlv_change, dec!(100));
+        assert_eq!(series.totals.fees, dec!(20));
+    }
+}
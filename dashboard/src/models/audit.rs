@@ -0,0 +1,76 @@
+use std::collections::BTreeMap;
+
+use axum::{Json, http::StatusCode, response::IntoResponse};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use utoipa::{IntoParams, ToSchema};
+use sqlx::Row;
+use sqlx::postgres::PgRow;
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub(crate) struct AuditRecord {
+    // Assigned by the database, zero until written
+    pub id: i64,
+    pub occurred_at: DateTime<Utc>,
+    // Absent when the request was rejected before authentication succeeded
+    pub subject: Option<String>,
+    pub role: Option<String>,
+    pub auth_method: Option<String>,
+    pub method: String,
+    // Matched route template, e.g. /journal/{local_id}
+    pub route: String,
+    pub path: String,
+    pub query: BTreeMap<String, String>,
+    pub status: i32,
+    // Unknown for streamed responses such as /events
+    pub response_bytes: Option<i64>,
+    pub duration_ms: i64,
+}
+
+impl<'r> sqlx::FromRow<'r, PgRow> for AuditRecord {
+    fn from_row(row: &'r PgRow) -> sqlx::Result<Self> {
+        Ok(AuditRecord {
+            id: row.try_get("id")?,
+            occurred_at: row.try_get("occurred_at")?,
+            subject: row.try_get("subject")?,
+            role: row.try_get("role")?,
+            auth_method: row.try_get("auth_method")?,
+            method: row.try_get("method")?,
+            route: row.try_get("route")?,
+            path: row.try_get("path")?,
+            query: row.try_get::<sqlx::types::Json<BTreeMap<String, String>>, _>("query")?.0,
+            status: row.try_get("status")?,
+            response_bytes: row.try_get("response_bytes")?,
+            duration_ms: row.try_get("duration_ms")?,
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub(crate) struct AuditRequest {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub subject: Option<String>,
+    pub route: Option<String>,
+    pub status: Option<i32>,
+    pub limit: Option<i64>,
+    // Page backwards by passing the smallest id of the previous page
+    pub before_id: Option<i64>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub(crate) struct AuditResponse {
+    pub response: Vec<AuditRecord>,
+}
+
+impl IntoResponse for AuditResponse {
+    fn into_response(self) -> axum::response::Response {
+        let body = Json(json!({
+            "audit": self
+        }));
+
+        (StatusCode::OK, body).into_response()
+    }
+}
@@ -0,0 +1,60 @@
+use axum::{Json, http::StatusCode, response::IntoResponse};
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use utoipa::{IntoParams, ToSchema};
+
+#[derive(Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub(crate) struct BenchmarkRequest {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub account_id: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Default, Clone, ToSchema)]
+pub(crate) struct BenchmarkStats {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub beta: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alpha_annual: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub correlation: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tracking_error: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub information_ratio: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub up_capture: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub down_capture: Option<f64>,
+    pub sample_days: usize,
+}
+
+// Cumulative P&L of the strategies and of holding the benchmark with BASE_CAPITAL
+#[derive(Serialize, Deserialize, Clone, ToSchema)]
+pub(crate) struct BenchmarkPoint {
+    pub date: NaiveDate,
+    pub strategy_equity: Decimal,
+    pub benchmark_equity: Decimal,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub(crate) struct BenchmarkResponse {
+    pub symbol: String,
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub stats: BenchmarkStats,
+    pub curve: Vec<BenchmarkPoint>,
+}
+
+impl IntoResponse for BenchmarkResponse {
+    fn into_response(self) -> axum::response::Response {
+        let body = Json(json!({
+            "benchmark": self
+        }));
+
+        (StatusCode::OK, body).into_response()
+    }
+}
@@ -0,0 +1,45 @@
+use axum::{Json, http::StatusCode, response::IntoResponse};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use utoipa::{IntoParams, ToSchema};
+
+#[derive(Debug, Copy, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum CorrelationGroup {
+    #[default]
+    Symbol,
+    StrategyType,
+}
+
+#[derive(Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub(crate) struct CorrelationRequest {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    #[serde(default)]
+    pub group_by: CorrelationGroup,
+    pub account_id: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub(crate) struct CorrelationResponse {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub group_by: CorrelationGroup,
+    // Labels and matrix rows/columns are both in hierarchical clustering order
+    pub labels: Vec<String>,
+    // Null where a series has no variance over the range
+    pub matrix: Vec<Vec<Option<f64>>>,
+    pub sample_days: usize,
+}
+
+impl IntoResponse for CorrelationResponse {
+    fn into_response(self) -> axum::response::Response {
+        let body = Json(json!({
+            "correlation": self
+        }));
+
+        (StatusCode::OK, body).into_response()
+    }
+}
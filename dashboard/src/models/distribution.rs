@@ -0,0 +1,86 @@
+use axum::{Json, http::StatusCode, response::IntoResponse};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use utoipa::{IntoParams, ToSchema};
+
+use super::strategy::StrategyType;
+
+#[derive(Debug, Copy, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum BinMethod {
+    // Width 2 * IQR / n^(1/3), falls back to Sturges when the IQR is zero
+    #[default]
+    FreedmanDiaconis,
+    // Equal width, from bin_width or bin_count
+    Fixed,
+    // Equal count, edges at the bin_count quantiles
+    Quantile,
+}
+
+#[derive(Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub(crate) struct DistributionRequest {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub account_id: Option<String>,
+    // Comma separated journal tags, see service::common::parse_tags
+    pub tags: Option<String>,
+    pub strategy_type: Option<StrategyType>,
+    #[serde(default)]
+    pub bins: BinMethod,
+    pub bin_count: Option<usize>,
+    pub bin_width: Option<f64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, ToSchema)]
+pub(crate) struct HistogramBin {
+    pub lower: f64,
+    // Exclusive except for the last bin
+    pub upper: f64,
+    pub count: usize,
+}
+
+#[derive(Serialize, Deserialize, Default, Clone, ToSchema)]
+pub(crate) struct SeriesDistribution {
+    pub count: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mean: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub std_dev: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub skew: Option<f64>,
+    // Excess kurtosis, 0 for a normal distribution
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kurtosis: Option<f64>,
+    // P1 to P99, index i holds percentile i + 1, empty without trades
+    pub percentiles: Vec<f64>,
+    pub bins: Vec<HistogramBin>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub(crate) struct DistributionResponse {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub currency: String,
+    pub bin_method: BinMethod,
+    // Net of fees, in the reporting currency
+    pub net_pnl: SeriesDistribution,
+    pub roi: SeriesDistribution,
+    pub fees: SeriesDistribution,
+    pub holding_hours: SeriesDistribution,
+}
+
+impl IntoResponse for DistributionResponse {
+    fn into_response(self) -> axum::response::Response {
+        let body = Json(json!({
+            "distribution": self
+        }));
+
+        (StatusCode::OK, body).into_response()
+    }
+}
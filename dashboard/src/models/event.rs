@@ -0,0 +1,68 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use sqlx::types::Uuid;
+
+use super::strategy::{Status, Strategy};
+
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub(crate) enum StrategyEventKind {
+    Opened,
+    Updated,
+    Closed,
+}
+
+impl fmt::Display for StrategyEventKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StrategyEventKind::Opened => write!(f, "opened"),
+            StrategyEventKind::Updated => write!(f, "updated"),
+            StrategyEventKind::Closed => write!(f, "closed"),
+        }
+    }
+}
+
+// Payload sent by the `strategy_notify` trigger, see deploy/sql/strategy_notify.sql
+#[derive(Debug, Deserialize)]
+pub(crate) struct StrategyNotification {
+    pub op: String,
+    pub local_id: Uuid,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub(crate) struct StrategyEvent {
+    pub kind: StrategyEventKind,
+    pub strategy: Strategy,
+}
+
+impl StrategyEvent {
+    pub fn new(op: &str, strategy: Strategy) -> Self {
+        let kind = match (op, strategy.status) {
+            (_, Status::Closed) => StrategyEventKind::Closed,
+            ("INSERT", Status::Open) => StrategyEventKind::Opened,
+            _ => StrategyEventKind::Updated,
+        };
+        StrategyEvent { kind, strategy }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kind(op: &str, status: Status) -> StrategyEventKind {
+        let mut strategy = Strategy::fixture();
+        strategy.status = status;
+        StrategyEvent::new(op, strategy).kind
+    }
+
+    #[test]
+    fn test_event_kind_follows_operation_and_status() {
+        assert_eq!(kind("INSERT", Status::Open), StrategyEventKind::Opened);
+        assert_eq!(kind("UPDATE", Status::Open), StrategyEventKind::Updated);
+        // Closing wins over the operation, including trades inserted already closed
+        assert_eq!(kind("UPDATE", Status::Closed), StrategyEventKind::Closed);
+        assert_eq!(kind("INSERT", Status::Closed), StrategyEventKind::Closed);
+    }
+}
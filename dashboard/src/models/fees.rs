@@ -0,0 +1,133 @@
+use axum::{Json, http::StatusCode, response::IntoResponse};
+use chrono::{DateTime, Datelike, Days, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use rust_decimal::prelude::ToPrimitive;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use utoipa::{IntoParams, ToSchema};
+use sqlx::types::Uuid;
+
+use super::strategy::{Strategy, StrategyType};
+
+#[derive(Debug, Copy, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum FeePeriod {
+    Day,
+    // Weeks start on Monday
+    Week,
+    #[default]
+    Month,
+}
+
+impl FeePeriod {
+    pub fn start_of(&self, date: NaiveDate) -> NaiveDate {
+        match self {
+            FeePeriod::Day => date,
+            FeePeriod::Week => date - Days::new(date.weekday().num_days_from_monday() as u64),
+            FeePeriod::Month => date.with_day(1).unwrap_or(date),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub(crate) struct FeeRequest {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub account_id: Option<String>,
+    // Comma separated journal tags, see service::common::parse_tags
+    pub tags: Option<String>,
+    pub strategy_type: Option<StrategyType>,
+    #[serde(default)]
+    pub period: FeePeriod,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize, ToSchema)]
+pub(crate) struct FeeBucket {
+    pub key: String,
+    pub trade_count: usize,
+    pub fees: Decimal,
+    // Before fees
+    pub gross_pnl: Decimal,
+    // Sum of the trades that were profitable before fees
+    pub gross_profit: Decimal,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fee_share_of_gross_profit: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub avg_fee: Option<Decimal>,
+}
+
+impl FeeBucket {
+    pub fn new(key: String) -> Self {
+        FeeBucket { key, ..Default::default() }
+    }
+
+    pub fn accumulate(&mut self, strategy: &Strategy) {
+        let stats = &strategy.risk.stats;
+        self.trade_count += 1;
+        self.fees += stats.fee;
+        self.gross_pnl += stats.pnl;
+        if stats.pnl > Decimal::ZERO {
+            self.gross_profit += stats.pnl;
+        }
+    }
+
+    pub fn finish(mut self) -> Self {
+        if self.gross_profit > Decimal::ZERO {
+            self.fee_share_of_gross_profit = (self.fees / self.gross_profit).to_f64();
+        }
+        if self.trade_count > 0 {
+            self.avg_fee = Some(self.fees / Decimal::from(self.trade_count));
+        }
+        self
+    }
+}
+
+// Winner before fees, loser after
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub(crate) struct FlippedTrade {
+    pub local_id: Uuid,
+    pub symbol: String,
+    pub contract: String,
+    pub exit_time: DateTime<Utc>,
+    pub pnl: Decimal,
+    pub fee: Decimal,
+    pub net: Decimal,
+}
+
+// Per-trade fees against the fees the broker booked on the account snapshots,
+// a positive difference is charged on the account but not attributed to a trade
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub(crate) struct FeeReconciliation {
+    pub account_id: String,
+    pub trade_fees: Decimal,
+    pub account_fees: Decimal,
+    pub difference: Decimal,
+    pub snapshot_days: usize,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub(crate) struct FeeReport {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub currency: String,
+    pub period: FeePeriod,
+    pub totals: FeeBucket,
+    pub by_period: Vec<FeeBucket>,
+    pub by_symbol: Vec<FeeBucket>,
+    pub by_strategy_type: Vec<FeeBucket>,
+    pub by_asset_type: Vec<FeeBucket>,
+    pub flipped_trades: Vec<FlippedTrade>,
+    // Empty when tags or strategy_type narrow the trades, account snapshots can't be narrowed the same way
+    pub reconciliation: Vec<FeeReconciliation>,
+}
+
+impl IntoResponse for FeeReport {
+    fn into_response(self) -> axum::response::Response {
+        let body = Json(json!({
+            "fees": self
+        }));
+
+        (StatusCode::OK, body).into_response()
+    }
+}
@@ -0,0 +1,113 @@
+use axum::{Json, http::StatusCode, response::IntoResponse};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use async_graphql::SimpleObject;
+use utoipa::{IntoParams, ToSchema};
+use sqlx::Row;
+use sqlx::postgres::PgRow;
+use sqlx::types::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, SimpleObject)]
+pub(crate) struct Attachment {
+    pub name: String,
+    // Relative to the configured attachments directory, served under /journal/files
+    pub path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_type: Option<String>,
+    pub size: u64,
+    pub uploaded_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, SimpleObject)]
+#[graphql(complex)]
+pub(crate) struct JournalEntry {
+    pub local_id: Uuid,
+    pub notes: String,
+    pub tags: Vec<String>,
+    pub attachments: Vec<Attachment>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl<'r> sqlx::FromRow<'r, PgRow> for JournalEntry {
+    fn from_row(row: &'r PgRow) -> sqlx::Result<Self> {
+        Ok(JournalEntry {
+            local_id: row.try_get("local_id")?,
+            notes: row.try_get("notes")?,
+            tags: row.try_get("tags")?,
+            attachments: row.try_get::<sqlx::types::Json<Vec<Attachment>>, _>("attachments")?.0,
+            created_at: row.try_get("created_at")?,
+            updated_at: row.try_get("updated_at")?,
+        })
+    }
+}
+
+// Body for create and update, omitted fields are left unchanged on update
+#[derive(Debug, Default, Serialize, Deserialize, ToSchema)]
+pub(crate) struct JournalUpdate {
+    pub notes: Option<String>,
+    pub tags: Option<Vec<String>>,
+}
+
+impl JournalUpdate {
+    pub fn normalized_tags(&self) -> Option<Vec<String>> {
+        self.tags.as_ref().map(|tags| normalize_tags(tags.iter().map(String::as_str)))
+    }
+}
+
+pub(crate) fn normalize_tags<'a>(tags: impl Iterator<Item = &'a str>) -> Vec<String> {
+    let mut tags: Vec<String> = tags
+        .map(|t| t.trim().to_lowercase())
+        .filter(|t| !t.is_empty())
+        .collect();
+    tags.sort();
+    tags.dedup();
+    tags
+}
+
+#[derive(Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub(crate) struct JournalListRequest {
+    // Comma separated, entries matching any tag are returned
+    pub tags: Option<String>,
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub(crate) struct AttachmentRequest {
+    pub name: String,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub(crate) struct JournalResponse {
+    pub response: Vec<JournalEntry>,
+}
+
+impl IntoResponse for JournalResponse {
+    fn into_response(self) -> axum::response::Response {
+        let body = Json(json!({
+            "journal": self
+        }));
+
+        (StatusCode::OK, body).into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tags_are_trimmed_lowercased_and_deduplicated() {
+        let tags = normalize_tags([" Earnings", "earnings", "", "  ", "FOMC", "fomc "].into_iter());
+        assert_eq!(tags, ["earnings", "fomc"]);
+    }
+
+    #[test]
+    fn test_update_leaves_tags_alone_unless_given() {
+        assert_eq!(JournalUpdate::default().normalized_tags(), None);
+        let clear = JournalUpdate { tags: Some(vec![" ".to_string()]), ..Default::default() };
+        assert_eq!(clear.normalized_tags(), Some(vec![]));
+    }
+}
@@ -1,17 +1,77 @@
 use chrono::NaiveDate;
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
+use async_graphql::SimpleObject;
+use utoipa::{IntoParams, ToSchema};
+use std::collections::BTreeMap;
+
+use super::strategy::StrategyType;
 
 // Hard-coded base capital for return normalization
 pub(crate) const BASE_CAPITAL: f64 = 5000.0;
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub(crate) struct MetricsRequest {
     pub from: NaiveDate,
     pub to: NaiveDate,
+    pub account_id: Option<String>,
+    // Comma separated journal tags, see service::common::parse_tags
+    pub tags: Option<String>,
+    // Also compute a metrics body per account alongside the consolidated one
+    #[serde(default)]
+    pub per_account: bool,
+    pub strategy_type: Option<StrategyType>,
+    // Comparison mode, any compare_* field set builds a second filter set from
+    // the primary one with those fields replaced
+    pub compare_from: Option<NaiveDate>,
+    pub compare_to: Option<NaiveDate>,
+    pub compare_account_id: Option<String>,
+    pub compare_tags: Option<String>,
+    pub compare_strategy_type: Option<StrategyType>,
+}
+
+impl MetricsRequest {
+    pub fn filter(&self) -> MetricsFilter {
+        MetricsFilter {
+            from: self.from,
+            to: self.to,
+            account_id: self.account_id.clone(),
+            tags: self.tags.clone(),
+            strategy_type: self.strategy_type,
+        }
+    }
+
+    pub fn comparison_filter(&self) -> Option<MetricsFilter> {
+        let compared = self.compare_from.is_some()
+            || self.compare_to.is_some()
+            || self.compare_account_id.is_some()
+            || self.compare_tags.is_some()
+            || self.compare_strategy_type.is_some();
+        if !compared {
+            return None;
+        }
+
+        Some(MetricsFilter {
+            from: self.compare_from.unwrap_or(self.from),
+            to: self.compare_to.unwrap_or(self.to),
+            account_id: self.compare_account_id.clone().or_else(|| self.account_id.clone()),
+            tags: self.compare_tags.clone().or_else(|| self.tags.clone()),
+            strategy_type: self.compare_strategy_type.or(self.strategy_type),
+        })
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct MetricsFilter {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub account_id: Option<String>,
+    pub tags: Option<String>,
+    pub strategy_type: Option<StrategyType>,
 }
 
-#[derive(Serialize, Deserialize, Default, Clone)]
+#[derive(Serialize, Deserialize, Default, Clone, ToSchema, SimpleObject)]
 pub(crate) struct DrawdownMetrics {
     pub max_dd_abs: Decimal,
     pub max_dd_pct_base: f64,
@@ -23,7 +83,7 @@ pub(crate) struct DrawdownMetrics {
     pub recovery_days: Option<i64>,
 }
 
-#[derive(Serialize, Deserialize, Default, Clone)]
+#[derive(Serialize, Deserialize, Default, Clone, ToSchema, SimpleObject)]
 pub(crate) struct SharpeMetrics {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub sharpe: Option<f64>,
@@ -35,7 +95,7 @@ pub(crate) struct SharpeMetrics {
     pub sample_days: usize,
 }
 
-#[derive(Serialize, Deserialize, Default, Clone)]
+#[derive(Serialize, Deserialize, Default, Clone, ToSchema, SimpleObject)]
 pub(crate) struct ProfitFactorMetrics {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub profit_factor: Option<f64>,
@@ -46,7 +106,7 @@ pub(crate) struct ProfitFactorMetrics {
     pub trade_count: usize,
 }
 
-#[derive(Serialize, Deserialize, Default, Clone)]
+#[derive(Serialize, Deserialize, Default, Clone, ToSchema, SimpleObject)]
 pub(crate) struct ExpectancyMetrics {
     pub expectancy_usd: Decimal,
     pub median_usd: Decimal,
@@ -57,7 +117,7 @@ pub(crate) struct ExpectancyMetrics {
     pub trade_count: usize,
 }
 
-#[derive(Serialize, Deserialize, Default, Clone)]
+#[derive(Serialize, Deserialize, Default, Clone, ToSchema, SimpleObject)]
 pub(crate) struct RecoveryFactorMetrics {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub recovery_factor: Option<f64>,
@@ -65,13 +125,60 @@ pub(crate) struct RecoveryFactorMetrics {
     pub reference_max_dd: Decimal,
 }
 
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Clone, ToSchema, SimpleObject)]
 pub(crate) struct MetricsResponseBody {
     pub from: NaiveDate,
     pub to: NaiveDate,
+    pub currency: String,
     pub drawdown: DrawdownMetrics,
     pub sharpe: SharpeMetrics,
     pub expectancy: ExpectancyMetrics,
     pub recovery: RecoveryFactorMetrics,
     pub profit_factor: ProfitFactorMetrics,
 }
+
+// Change from the primary body to the compared one, relative is against |base|
+#[derive(Serialize, Deserialize, Default, Clone, ToSchema)]
+pub(crate) struct MetricDelta {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub base: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub compared: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub absolute: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub relative: Option<f64>,
+}
+
+#[derive(Serialize, Deserialize, Default, Clone, ToSchema)]
+pub(crate) struct WelchTest {
+    pub t: f64,
+    pub df: f64,
+    pub p_value: f64,
+}
+
+#[derive(Serialize, Deserialize, Default, Clone, ToSchema)]
+pub(crate) struct BootstrapTest {
+    pub samples: usize,
+    pub p_value: f64,
+}
+
+// Two-sided tests of equal mean net P&L per trade
+#[derive(Serialize, Deserialize, Default, Clone, ToSchema)]
+pub(crate) struct SignificanceMetrics {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mean_base: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mean_compared: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub welch: Option<WelchTest>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bootstrap: Option<BootstrapTest>,
+}
+
+#[derive(Serialize, Deserialize, Clone, ToSchema)]
+pub(crate) struct MetricsComparison {
+    pub metrics: MetricsResponseBody,
+    pub deltas: BTreeMap<String, MetricDelta>,
+    pub significance: SignificanceMetrics,
+}
@@ -1,6 +1,8 @@
 use std::fmt;
 
 use serde::{Deserialize, Serialize};
+use async_graphql::Enum;
+use utoipa::ToSchema;
 use sqlx::FromRow;
 use sqlx::Row;
 use sqlx::postgres::PgRow;
@@ -13,21 +15,39 @@ pub(super) mod settings;
 pub(super) mod watermark;
 pub(super) mod metrics;
 pub(super) mod account;
+pub(super) mod audit;
+pub(super) mod benchmark;
+pub(super) mod correlation;
+pub(super) mod distribution;
+pub(super) mod event;
+pub(super) mod fees;
+pub(super) mod journal;
+pub(super) mod position;
+pub(super) mod risk_report;
+pub(super) mod view;
 
+// Root shared across contract rolls and expiries, see symbology::SymbolParser
 fn get_alias(symbol: &str) -> String {
-    if symbol.starts_with("/") {
-        return symbol[..3].to_string();
-    }
-    symbol.to_string()
+    crate::symbology::parser().root_of(symbol)
 }
 
-#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema, Enum)]
 pub(crate) enum Side {
     Call,
     Put,
     Netural,
 }
 
+impl fmt::Display for Side {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Side::Call => write!(f, "Call"),
+            Side::Put => write!(f, "Put"),
+            Side::Netural => write!(f, "Neutral"),
+        }
+    }
+}
+
 impl<'r> FromRow<'r, PgRow> for Side {
     fn from_row(row: &'r PgRow) -> Result<Self, sqlx::Error> {
         let ty: i32 = row.try_get("side")?;
@@ -39,7 +59,7 @@ impl<'r> FromRow<'r, PgRow> for Side {
     }
 }
 
-#[derive(Debug, Clone, Default, Copy, PartialEq, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, Copy, PartialEq, Eq, Deserialize, Serialize, ToSchema, Enum)]
 pub enum PriceEffect {
     #[default]
     Credit,
@@ -75,7 +95,7 @@ impl fmt::Display for PriceEffect {
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq, ToSchema, Enum)]
 pub enum AssetType {
     #[default]
     Equity,
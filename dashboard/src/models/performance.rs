@@ -3,18 +3,20 @@ use chrono::NaiveDate;
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
-use tracing::info;
+use utoipa::{IntoParams, ToSchema};
 
 use crate::models::{get_alias, strategy::Strategy};
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub(crate) struct PerformanceRequest {
     pub from: NaiveDate,
     pub to: NaiveDate,
     pub is_active: bool,
+    pub account_id: Option<String>,
 }
 
-#[derive(Deserialize, Serialize)]
+#[derive(Deserialize, Serialize, ToSchema)]
 pub(crate) struct Performance {
     pub strategy: String,
     pub start_date: NaiveDate,
@@ -28,7 +30,7 @@ pub(crate) struct Performance {
 
 impl From<&Strategy> for Performance {
     fn from(strategy: &Strategy) -> Self {
-        let perf = Performance {
+        Performance {
             strategy: get_alias(&strategy.symbol),
             start_date: strategy.entry_time.date_naive(),
             exit_date: strategy.exit_time.date_naive(),
@@ -37,12 +39,11 @@ impl From<&Strategy> for Performance {
             pnl: strategy.risk.stats.pnl,
             roi: strategy.risk.stats.roi,
             fee: strategy.risk.stats.fee,
-        };
-        perf
+        }
     }
 }
 
-#[derive(Deserialize, Serialize)]
+#[derive(Deserialize, Serialize, ToSchema)]
 pub(crate) struct PerformanceResponse {
     pub response: Vec<Performance>,
 }
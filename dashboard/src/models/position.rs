@@ -0,0 +1,204 @@
+use std::fmt;
+
+use axum::{Json, http::StatusCode, response::IntoResponse};
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use utoipa::ToSchema;
+use sqlx::types::Uuid;
+
+use super::{
+    AssetType, PriceEffect, Side,
+    strategy::{Strategy, StrategyType},
+};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub(crate) enum Direction {
+    Long,
+    Short,
+}
+
+impl From<PriceEffect> for Direction {
+    fn from(price_effect: PriceEffect) -> Self {
+        match price_effect {
+            PriceEffect::Debit => Direction::Long,
+            PriceEffect::Credit => Direction::Short,
+        }
+    }
+}
+
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Direction::Long => write!(f, "Long"),
+            Direction::Short => write!(f, "Short"),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub(crate) struct OpenPosition {
+    pub local_id: Uuid,
+    pub symbol: String,
+    pub contract: String,
+    pub underlying: String,
+    pub asset_type: AssetType,
+    pub strategy_type: StrategyType,
+    pub side: Side,
+    pub price_effect: PriceEffect,
+    pub direction: Direction,
+    pub entry_time: DateTime<Utc>,
+    pub days_in_trade: f64,
+    pub open_price: Decimal,
+    pub current_price: Decimal,
+    // In dollars, current_notional - open_notional
+    pub unrealized_pnl: Decimal,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unrealized_pct: Option<Decimal>,
+    pub profit_target: Decimal,
+    pub distance_to_target: Decimal,
+    pub stop: Decimal,
+    pub distance_to_stop: Decimal,
+    pub watermark: Decimal,
+    // Prices times the contract multiplier, negative for short positions
+    pub open_notional: Decimal,
+    pub current_notional: Decimal,
+}
+
+impl OpenPosition {
+    pub fn new(strategy: &Strategy, as_of: DateTime<Utc>) -> Self {
+        let gain = &strategy.risk.gain;
+        let loss = &strategy.risk.loss;
+
+        // Credits profit as the price falls, debits as it rises
+        let direction = match strategy.meta.price_effect {
+            PriceEffect::Credit => Decimal::NEGATIVE_ONE,
+            PriceEffect::Debit => Decimal::ONE,
+        };
+        let unrealized_pct = if gain.open.is_zero() {
+            None
+        } else {
+            Some((gain.current - gain.open) * direction / gain.open.abs())
+        };
+        let open_notional = strategy.signed_notional(gain.open);
+        let current_notional = strategy.signed_notional(gain.current);
+
+        let held = as_of - strategy.entry_time;
+
+        OpenPosition {
+            local_id: strategy.local_id,
+            symbol: strategy.symbol.clone(),
+            contract: strategy.contract.clone(),
+            underlying: strategy.meta.underlying.clone(),
+            asset_type: strategy.meta.asset_type,
+            strategy_type: strategy.meta.r#type,
+            side: strategy.meta.side,
+            price_effect: strategy.meta.price_effect,
+            direction: strategy.meta.price_effect.into(),
+            entry_time: strategy.entry_time,
+            days_in_trade: held.num_seconds() as f64 / 86_400.0,
+            open_price: gain.open,
+            current_price: gain.current,
+            unrealized_pnl: current_notional - open_notional,
+            unrealized_pct,
+            profit_target: gain.target,
+            distance_to_target: (gain.target - gain.current) * direction,
+            stop: loss.target,
+            distance_to_stop: (gain.current - loss.target) * direction,
+            watermark: loss.watermark,
+            open_notional,
+            current_notional,
+        }
+    }
+}
+
+// Values are signed notionals, so long and short positions in a bucket offset and
+// unrealized_pnl is current_value - open_value in dollars, like each position's
+#[derive(Serialize, Deserialize, Default, Clone, ToSchema)]
+pub(crate) struct ExposureBucket {
+    pub key: String,
+    pub positions: usize,
+    pub open_value: Decimal,
+    pub current_value: Decimal,
+    pub unrealized_pnl: Decimal,
+}
+
+impl ExposureBucket {
+    pub fn add(&mut self, position: &OpenPosition) {
+        self.positions += 1;
+        self.open_value += position.open_notional;
+        self.current_value += position.current_notional;
+        self.unrealized_pnl += position.unrealized_pnl;
+    }
+}
+
+#[derive(Serialize, Deserialize, Default, ToSchema)]
+pub(crate) struct ExposureSummary {
+    pub by_underlying: Vec<ExposureBucket>,
+    pub by_asset_type: Vec<ExposureBucket>,
+    // Long for debit positions, short for credit
+    pub by_side: Vec<ExposureBucket>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub(crate) struct PositionsResponse {
+    pub as_of: DateTime<Utc>,
+    pub response: Vec<OpenPosition>,
+    pub exposure: ExposureSummary,
+}
+
+impl IntoResponse for PositionsResponse {
+    fn into_response(self) -> axum::response::Response {
+        let body = Json(json!({
+            "positions": self
+        }));
+
+        (StatusCode::OK, body).into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::AssetType;
+    use rust_decimal_macros::dec;
+
+    fn position(symbol: &str, asset_type: AssetType, price_effect: PriceEffect, open: Decimal, current: Decimal) -> OpenPosition {
+        let mut strategy = Strategy::fixture();
+        strategy.symbol = symbol.to_string();
+        strategy.meta.asset_type = asset_type;
+        strategy.meta.price_effect = price_effect;
+        strategy.risk.gain.open = open;
+        strategy.risk.gain.current = current;
+        OpenPosition::new(&strategy, strategy.entry_time)
+    }
+
+    #[test]
+    fn test_notional_is_signed_and_scaled_by_multiplier() {
+        let short_put = position("SPY", AssetType::EquityOption, PriceEffect::Credit, dec!(1.50), dec!(0.50));
+        assert_eq!(short_put.direction, Direction::Short);
+        assert_eq!(short_put.open_notional, dec!(-150));
+        assert_eq!(short_put.current_notional, dec!(-50));
+        assert_eq!(short_put.unrealized_pnl, dec!(100));
+        assert_eq!(short_put.unrealized_pct, Some(dec!(0.6666666666666666666666666667)));
+
+        let long_future = position("/ES", AssetType::Future, PriceEffect::Debit, dec!(5000), dec!(5010));
+        assert_eq!(long_future.direction, Direction::Long);
+        assert_eq!(long_future.open_notional, dec!(250000));
+        assert_eq!(long_future.unrealized_pnl, dec!(500));
+    }
+
+    #[test]
+    fn test_bucket_offsets_long_and_short_and_reports_dollar_pnl() {
+        let mut bucket = ExposureBucket::default();
+        // Short put gains $100, long call loses $40
+        bucket.add(&position("SPY", AssetType::EquityOption, PriceEffect::Credit, dec!(1.50), dec!(0.50)));
+        bucket.add(&position("SPY", AssetType::EquityOption, PriceEffect::Debit, dec!(2.00), dec!(1.60)));
+
+        assert_eq!(bucket.positions, 2);
+        assert_eq!(bucket.open_value, dec!(50));
+        assert_eq!(bucket.current_value, dec!(110));
+        assert_eq!(bucket.unrealized_pnl, dec!(60));
+    }
+}
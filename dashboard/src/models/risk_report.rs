@@ -0,0 +1,44 @@
+use axum::{Json, http::StatusCode, response::IntoResponse};
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use utoipa::ToSchema;
+
+use super::settings::UnderlyingLimit;
+
+#[derive(Serialize, Deserialize, Default, Clone, ToSchema)]
+pub(crate) struct ConcentrationRow {
+    pub key: String,
+    pub positions: usize,
+    pub exposure: Decimal,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exposure_pct: Option<f64>,
+    // Positive for net credit received, negative for net debit paid
+    pub net_premium: Decimal,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub net_premium_pct: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<UnderlyingLimit>,
+    pub breaches: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub(crate) struct RiskReport {
+    pub as_of: DateTime<Utc>,
+    pub currency: String,
+    pub net_liquidating_value: Decimal,
+    pub by_underlying: Vec<ConcentrationRow>,
+    pub by_root: Vec<ConcentrationRow>,
+    pub breach_count: usize,
+}
+
+impl IntoResponse for RiskReport {
+    fn into_response(self) -> axum::response::Response {
+        let body = Json(json!({
+            "risk": self
+        }));
+
+        (StatusCode::OK, body).into_response()
+    }
+}
@@ -1,15 +1,17 @@
 use super::Side;
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
+use async_graphql::SimpleObject;
+use utoipa::ToSchema;
 
-#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, ToSchema, SimpleObject)]
 pub(crate) struct Gain {
     pub open: Decimal,
     pub current: Decimal,
     pub target: Decimal,
 }
 
-#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, ToSchema, SimpleObject)]
 pub(crate) struct Loss {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub lower: Option<Decimal>,
@@ -19,7 +21,7 @@ pub(crate) struct Loss {
     pub watermark: Decimal,
 }
 
-#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, ToSchema, SimpleObject)]
 pub(crate) struct Stats {
     pub pnl: Decimal,
     pub roi: Decimal,
@@ -27,7 +29,7 @@ pub(crate) struct Stats {
     pub fee: Decimal,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema, SimpleObject)]
 pub(crate) struct RiskData {
     pub side: Side,
     #[serde(default)]
@@ -1,8 +1,289 @@
+use std::collections::HashMap;
+
 use common::{aws_logging::LoggingConfig, db_client::DatabaseConfig};
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct UnderlyingLimit {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_positions: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_exposure_pct: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_net_premium_pct: Option<f64>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RiskLimits {
+    // Applied to any underlying/root without an explicit entry
+    pub default: Option<UnderlyingLimit>,
+    pub underlyings: HashMap<String, UnderlyingLimit>,
+}
+
+impl RiskLimits {
+    pub fn limit_for(&self, key: &str) -> Option<&UnderlyingLimit> {
+        self.underlyings.get(key).or(self.default.as_ref())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FxConfig {
+    pub reporting_currency: String,
+    // JSON array of {"date", "currency", "rate"} where rate converts one unit of
+    // `currency` into the reporting currency
+    pub rates_file: Option<String>,
+    // Postgres table with the same date/currency/rate columns, used when no file is set
+    pub rates_table: Option<String>,
+}
+
+impl Default for FxConfig {
+    fn default() -> Self {
+        FxConfig {
+            reporting_currency: "USD".to_string(),
+            rates_file: None,
+            rates_table: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BenchmarkConfig {
+    pub symbol: String,
+    // CSV with a `date,close` header, one row per trading day
+    pub csv_file: Option<String>,
+    // Postgres table with `symbol`, `date` and `close` columns, used when no file is set
+    pub table: Option<String>,
+}
+
+impl Default for BenchmarkConfig {
+    fn default() -> Self {
+        BenchmarkConfig {
+            symbol: "SPY".to_string(),
+            csv_file: None,
+            table: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CalendarConfig {
+    // IANA timezone the exchange session is reckoned in
+    pub timezone: String,
+    // JSON array of ISO dates the exchange is closed
+    pub holidays_file: Option<String>,
+}
+
+impl Default for CalendarConfig {
+    fn default() -> Self {
+        CalendarConfig {
+            timezone: "America/New_York".to_string(),
+            holidays_file: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SymbologyConfig {
+    // Known futures roots, with or without the leading slash
+    pub futures_roots: Vec<String>,
+    // Dollar value of one point per futures root, futures options use their root's
+    pub contract_multipliers: HashMap<String, Decimal>,
+}
+
+impl Default for SymbologyConfig {
+    fn default() -> Self {
+        let roots = [
+            "ES", "MES", "NQ", "MNQ", "RTY", "M2K", "YM", "MYM", "ZN", "ZB", "ZF", "ZT", "UB", "CL", "MCL",
+            "NG", "GC", "MGC", "SI", "SIL", "HG", "6E", "6J", "6B", "6A", "6C", "ZC", "ZS", "ZW", "VX",
+        ];
+        let multipliers = [
+            ("ES", 50), ("MES", 5), ("NQ", 20), ("MNQ", 2), ("RTY", 50), ("M2K", 5), ("YM", 5), ("ZN", 1_000),
+            ("ZB", 1_000), ("ZF", 1_000), ("ZT", 2_000), ("UB", 1_000), ("CL", 1_000), ("MCL", 100),
+            ("NG", 10_000), ("GC", 100), ("MGC", 10), ("SI", 5_000), ("SIL", 1_000), ("HG", 25_000),
+            ("6E", 125_000), ("6J", 12_500_000), ("6B", 62_500), ("6A", 100_000), ("6C", 100_000), ("ZC", 50),
+            ("ZS", 50), ("ZW", 50), ("VX", 1_000),
+        ];
+        let mut contract_multipliers: HashMap<String, Decimal> =
+            multipliers.iter().map(|(root, m)| (root.to_string(), Decimal::from(*m))).collect();
+        // Micro Dow is 50 cents a point
+        contract_multipliers.insert("MYM".to_string(), Decimal::new(5, 1));
+        SymbologyConfig {
+            futures_roots: roots.iter().map(|r| r.to_string()).collect(),
+            contract_multipliers,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct JournalConfig {
+    // Local directory attachments are written to and served from
+    pub attachments_dir: String,
+    pub max_attachment_bytes: usize,
+}
+
+impl Default for JournalConfig {
+    fn default() -> Self {
+        JournalConfig {
+            attachments_dir: "journal-attachments".to_string(),
+            max_attachment_bytes: 10 * 1024 * 1024,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ApiConfig {
+    // Widest from/to span a query may ask for
+    pub max_range_days: i64,
+    pub request_timeout_secs: u64,
+}
+
+impl Default for ApiConfig {
+    fn default() -> Self {
+        ApiConfig {
+            max_range_days: 3660,
+            request_timeout_secs: 30,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AuthConfig {
+    // Only for local development, every request is treated as an admin
+    pub enabled: bool,
+    pub issuer: Option<String>,
+    pub audience: Option<String>,
+    // JWKS is read from the file when set, otherwise fetched from the URL
+    pub jwks_url: Option<String>,
+    pub jwks_file: Option<String>,
+    // Claim holding role names, either an array or a space separated string
+    pub roles_claim: String,
+    pub leeway_secs: i64,
+    // Minimum gap between JWKS refetches triggered by an unknown key id
+    pub jwks_refresh_secs: u64,
+    // Parameter Store name of a JSON list of {"name", "key", "role"} entries
+    pub api_keys_parameter: Option<String>,
+}
+
+impl Default for AuthConfig {
+    fn default() -> Self {
+        AuthConfig {
+            enabled: true,
+            issuer: None,
+            audience: None,
+            jwks_url: None,
+            jwks_file: None,
+            roles_claim: "roles".to_string(),
+            leeway_secs: 60,
+            jwks_refresh_secs: 300,
+            api_keys_parameter: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CorsConfig {
+    // Exact origins, e.g. https://dashboard.example.com. Empty allows same-origin only
+    pub allowed_origins: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AuditConfig {
+    pub enabled: bool,
+    // Also log each record as JSON through tracing, which the CloudWatch layer ships
+    pub cloudwatch: bool,
+    // Records queued for the writer before new ones are dropped
+    pub buffer: usize,
+}
+
+impl Default for AuditConfig {
+    fn default() -> Self {
+        AuditConfig {
+            enabled: true,
+            cloudwatch: false,
+            buffer: 1024,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ShutdownConfig {
+    // Readiness fails this long before the listener closes so the load balancer stops routing first
+    pub readiness_delay_secs: u64,
+    // Longest wait for in-flight requests once the listener has closed
+    pub drain_timeout_secs: u64,
+    // Longest wait for queued audit records and log batches after the drain
+    pub flush_timeout_secs: u64,
+}
+
+impl Default for ShutdownConfig {
+    fn default() -> Self {
+        ShutdownConfig {
+            readiness_delay_secs: 5,
+            drain_timeout_secs: 20,
+            flush_timeout_secs: 5,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PrometheusConfig {
+    pub enabled: bool,
+    // Scrape path on the API listener, /metrics already serves the trading metrics
+    pub path: String,
+    // Serve the scrape path on its own unauthenticated listener instead of behind API auth
+    pub port: Option<u16>,
+}
+
+impl Default for PrometheusConfig {
+    fn default() -> Self {
+        PrometheusConfig {
+            enabled: true,
+            path: "/prometheus".to_string(),
+            port: None,
+        }
+    }
+}
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Settings {
     pub database: DatabaseConfig,
     pub logging: LoggingConfig,
-}
\ No newline at end of file
+    #[serde(default)]
+    pub risk: RiskLimits,
+    #[serde(default)]
+    pub fx: FxConfig,
+    #[serde(default)]
+    pub benchmark: BenchmarkConfig,
+    #[serde(default)]
+    pub calendar: CalendarConfig,
+    #[serde(default)]
+    pub symbols: SymbologyConfig,
+    #[serde(default)]
+    pub journal: JournalConfig,
+    #[serde(default)]
+    pub api: ApiConfig,
+    #[serde(default)]
+    pub auth: AuthConfig,
+    #[serde(default)]
+    pub cors: CorsConfig,
+    #[serde(default)]
+    pub audit: AuditConfig,
+    #[serde(default)]
+    pub shutdown: ShutdownConfig,
+    #[serde(default)]
+    pub prometheus: PrometheusConfig,
+}
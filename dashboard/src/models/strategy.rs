@@ -7,6 +7,8 @@ use chrono::Utc;
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
+use async_graphql::{Enum, SimpleObject};
+use utoipa::ToSchema;
 use sqlx::FromRow;
 use sqlx::Row;
 use sqlx::postgres::PgRow;
@@ -20,7 +22,7 @@ use super::Side;
 use super::riskdata::RiskData;
 use super::account::AccountDailySnapshot;
 
-#[derive(Debug, Clone, PartialEq, FromRow, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, FromRow, Serialize, Deserialize, ToSchema, SimpleObject)]
 pub(crate) struct Metadata {
     pub local_id: Uuid,
     pub underlying: String,
@@ -32,7 +34,7 @@ pub(crate) struct Metadata {
     pub side: Side,
 }
 
-#[derive(Debug, Copy, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Deserialize, Serialize, ToSchema, Enum)]
 pub(crate) enum Status {
     Open,
     #[default]
@@ -77,7 +79,7 @@ impl<'r> sqlx::Decode<'r, sqlx::Postgres> for Status {
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq, ToSchema, Enum)]
 pub enum StrategyType {
     SingleLeg,
     CreditSpread,
@@ -119,10 +121,15 @@ impl<'r> sqlx::Decode<'r, sqlx::Postgres> for StrategyType {
 }
 
 //"strategy": "local_id UUID, symbol VARCHAR, entry_time TIMESTAMPTZ, exit_time TIMESTAMPTZ, status INT, cfg JSON, metadata JSON, risk JSON, orders JSON",
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, SimpleObject)]
+#[graphql(complex)]
 pub(crate) struct Strategy {
     pub local_id: Uuid,
+    // Root the contract aggregates under, e.g. /ES for /ESZ4
     pub symbol: String,
+    // Symbol as traded, keeps contract month and option details
+    #[serde(default)]
+    pub contract: String,
     pub entry_time: DateTime<Utc>,
     pub exit_time: DateTime<Utc>,
     pub status: Status,
@@ -134,9 +141,11 @@ pub(crate) struct Strategy {
 
 impl<'r> sqlx::FromRow<'r, PgRow> for Strategy {
     fn from_row(row: &'r PgRow) -> sqlx::Result<Self> {
+        let contract: String = row.try_get("symbol")?;
         Ok(Strategy {
             local_id: row.try_get("local_id")?,
-            symbol: get_alias(row.try_get("symbol")?),
+            symbol: get_alias(&contract),
+            contract,
             entry_time: row.try_get("entry_time")?,
             exit_time: row.try_get("exit_time")?,
             status: row.try_get("status")?,
@@ -147,7 +156,25 @@ impl<'r> sqlx::FromRow<'r, PgRow> for Strategy {
     }
 }
 
-#[derive(Deserialize, Serialize)]
+impl Strategy {
+    /// A root such as /ES spans every contract roll, anything else is an exact contract
+    pub fn traded_as(&self, symbol: &str) -> bool {
+        self.symbol == symbol || self.contract == symbol
+    }
+
+    /// Premium times the contract multiplier, positive for a debit paid and negative
+    /// for a credit received
+    pub fn signed_notional(&self, price: Decimal) -> Decimal {
+        let multiplier = crate::symbology::parser().multiplier(&self.symbol, self.meta.asset_type);
+        let notional = price.abs() * multiplier;
+        match self.meta.price_effect {
+            PriceEffect::Debit => notional,
+            PriceEffect::Credit => -notional,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, ToSchema)]
 pub struct StrategyResponse {
     pub response: Vec<Strategy>,
 }
@@ -161,3 +188,36 @@ impl IntoResponse for StrategyResponse {
         (StatusCode::OK, body).into_response()
     }
 }
+
+#[cfg(test)]
+impl Strategy {
+    // Closed one-lot credit trade on SPY opened at 1.00, tests override what they exercise
+    pub fn fixture() -> Self {
+        let local_id = Uuid::nil();
+        Strategy {
+            local_id,
+            symbol: "SPY".to_string(),
+            contract: "SPY".to_string(),
+            entry_time: "2025-03-10T14:30:00Z".parse().unwrap(),
+            exit_time: "2025-03-12T20:00:00Z".parse().unwrap(),
+            status: Status::Closed,
+            meta: Metadata {
+                local_id,
+                underlying: "SPY".to_string(),
+                price_effect: PriceEffect::Credit,
+                asset_type: AssetType::EquityOption,
+                r#type: StrategyType::SingleLeg,
+                status: Status::Closed,
+                open_price: Decimal::ONE,
+                side: Side::Put,
+            },
+            risk: RiskData {
+                side: Side::Put,
+                gain: Default::default(),
+                loss: Default::default(),
+                stats: Default::default(),
+            },
+            account: AccountDailySnapshot::default(),
+        }
+    }
+}
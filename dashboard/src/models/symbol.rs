@@ -1,34 +1,121 @@
-
 use axum::{Json, http::StatusCode, response::IntoResponse};
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
-use sqlx::Row;
-use sqlx::postgres::PgRow;
+use async_graphql::{Enum, SimpleObject};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::symbology::ParsedSymbol;
+
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema, Enum)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum SymbolSort {
+    // Open positions first, then most recently traded
+    #[default]
+    Active,
+    Name,
+    LastTrade,
+    TradeCount,
+    Pnl,
+}
 
-use crate::models::get_alias;
+#[derive(Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub(crate) struct SymbolRequest {
+    pub account_id: Option<String>,
+    // Case-insensitive prefix on the root or any of its contracts
+    pub prefix: Option<String>,
+    #[serde(default)]
+    pub sort: SymbolSort,
+}
+
+// Per contract and account currency aggregates straight from the strategy table
+#[derive(Debug, sqlx::FromRow)]
+pub(crate) struct SymbolActivity {
+    pub symbol: String,
+    pub currency: Option<String>,
+    pub first_trade: DateTime<Utc>,
+    pub last_trade: DateTime<Utc>,
+    pub trade_count: i64,
+    pub open_count: i64,
+    pub closed_count: i64,
+    pub wins: i64,
+    pub net_pnl: Decimal,
+    pub asset_types: Vec<String>,
+}
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, ToSchema, SimpleObject)]
+#[graphql(complex)]
 pub(crate) struct Symbol {
     pub name: String,
+    // Individual contracts traded under this root
+    #[serde(default)]
+    #[graphql(skip)]
+    pub contracts: Vec<ParsedSymbol>,
+    pub first_trade: DateTime<Utc>,
+    pub last_trade: DateTime<Utc>,
+    pub trade_count: i64,
+    pub open_positions: i64,
+    pub closed_trades: i64,
+    pub net_pnl: Decimal,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub win_rate: Option<f64>,
+    pub asset_types: Vec<String>,
+    #[serde(skip)]
+    #[graphql(skip)]
+    wins: i64,
 }
 
-impl<'r> sqlx::FromRow<'r, PgRow> for Symbol {
-    fn from_row(row: &'r PgRow) -> sqlx::Result<Self> {
-        Ok(Symbol {
-            name: row.try_get("symbol")?,
-        })
+impl Symbol {
+    pub fn new(name: String, first_trade: DateTime<Utc>, last_trade: DateTime<Utc>) -> Self {
+        Symbol {
+            name,
+            contracts: Vec::new(),
+            first_trade,
+            last_trade,
+            trade_count: 0,
+            open_positions: 0,
+            closed_trades: 0,
+            net_pnl: Decimal::ZERO,
+            win_rate: None,
+            asset_types: Vec::new(),
+            wins: 0,
+        }
     }
-}
 
-impl From<&Symbol> for Symbol {
-    fn from(symbol: &Symbol) -> Self {
-        Symbol {
-            name: get_alias(&symbol.name),
+    /// Fold a contract's activity into this root, `net_pnl` already in reporting currency
+    pub fn accumulate(&mut self, activity: &SymbolActivity, contract: ParsedSymbol, net_pnl: Decimal) {
+        if !self.contracts.iter().any(|c| c.raw == contract.raw) {
+            self.contracts.push(contract);
+        }
+        self.first_trade = self.first_trade.min(activity.first_trade);
+        self.last_trade = self.last_trade.max(activity.last_trade);
+        self.trade_count += activity.trade_count;
+        self.open_positions += activity.open_count;
+        self.closed_trades += activity.closed_count;
+        self.wins += activity.wins;
+        self.net_pnl += net_pnl;
+        for asset_type in &activity.asset_types {
+            if !self.asset_types.contains(asset_type) {
+                self.asset_types.push(asset_type.clone());
+            }
         }
+        self.win_rate = if self.closed_trades > 0 {
+            Some(self.wins as f64 / self.closed_trades as f64)
+        } else {
+            None
+        };
+    }
+
+    pub fn matches_prefix(&self, prefix: &str) -> bool {
+        let prefix = prefix.to_uppercase();
+        let matches = |s: &str| s.to_uppercase().trim_start_matches(['.', '/']).starts_with(prefix.trim_start_matches(['.', '/']));
+        matches(&self.name) || self.contracts.iter().any(|c| matches(&c.raw))
     }
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, ToSchema)]
 pub(crate) struct SymbolResponse {
     pub response: Vec<Symbol>,
 }
@@ -42,3 +129,62 @@ impl IntoResponse for SymbolResponse {
         (StatusCode::OK, body).into_response()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::symbology;
+    use chrono::{Datelike, TimeZone};
+    use rust_decimal_macros::dec;
+
+    fn activity(contract: &str, day: u32, open_count: i64, closed_count: i64, wins: i64) -> SymbolActivity {
+        let ts = Utc.with_ymd_and_hms(2025, 3, day, 15, 0, 0).unwrap();
+        SymbolActivity {
+            symbol: contract.to_string(),
+            currency: Some("USD".to_string()),
+            first_trade: ts,
+            last_trade: ts,
+            trade_count: open_count + closed_count,
+            open_count,
+            closed_count,
+            wins,
+            net_pnl: Decimal::ZERO,
+            asset_types: vec!["Future".to_string()],
+        }
+    }
+
+    fn es() -> Symbol {
+        let parser = symbology::parser();
+        let mut symbol = Symbol::new("/ES".to_string(), Utc::now(), Utc.timestamp_opt(0, 0).unwrap());
+        for (contract, day, open, closed, wins, pnl) in
+            [("/ESH5", 10, 0, 4, 3, dec!(300)), ("/ESM5", 12, 1, 2, 0, dec!(-100)), ("/ESH5", 14, 0, 0, 0, dec!(0))]
+        {
+            let activity = activity(contract, day, open, closed, wins);
+            symbol.accumulate(&activity, parser.parse(contract, activity.first_trade.date_naive()), pnl);
+        }
+        symbol
+    }
+
+    #[test]
+    fn test_accumulate_merges_contracts_and_counts() {
+        let symbol = es();
+        let contracts: Vec<&str> = symbol.contracts.iter().map(|c| c.raw.as_str()).collect();
+        assert_eq!(contracts, ["/ESH5", "/ESM5"]);
+        assert_eq!((symbol.trade_count, symbol.open_positions, symbol.closed_trades), (7, 1, 6));
+        assert_eq!(symbol.net_pnl, dec!(200));
+        assert_eq!(symbol.win_rate, Some(0.5));
+        assert_eq!(symbol.asset_types, ["Future"]);
+        assert_eq!(symbol.first_trade.day(), 10);
+        assert_eq!(symbol.last_trade.day(), 14);
+    }
+
+    #[test]
+    fn test_prefix_matches_root_or_contract_ignoring_case_and_slashes() {
+        let symbol = es();
+        assert!(symbol.matches_prefix("es"));
+        assert!(symbol.matches_prefix("/ES"));
+        assert!(symbol.matches_prefix("./esm"));
+        assert!(!symbol.matches_prefix("ESZ"));
+        assert!(!symbol.matches_prefix("NQ"));
+    }
+}
@@ -0,0 +1,86 @@
+use std::collections::BTreeMap;
+
+use axum::{Json, http::StatusCode, response::IntoResponse};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use utoipa::{IntoParams, ToSchema};
+use sqlx::Row;
+use sqlx::postgres::PgRow;
+use sqlx::types::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub(crate) struct SavedView {
+    pub id: Uuid,
+    pub name: String,
+    // Route the view was saved from, e.g. /metrics
+    pub endpoint: String,
+    pub params: BTreeMap<String, serde_json::Value>,
+    pub owner: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl<'r> sqlx::FromRow<'r, PgRow> for SavedView {
+    fn from_row(row: &'r PgRow) -> sqlx::Result<Self> {
+        Ok(SavedView {
+            id: row.try_get("id")?,
+            name: row.try_get("name")?,
+            endpoint: row.try_get("endpoint")?,
+            params: row.try_get::<sqlx::types::Json<BTreeMap<String, serde_json::Value>>, _>("params")?.0,
+            owner: row.try_get("owner")?,
+            created_at: row.try_get("created_at")?,
+            updated_at: row.try_get("updated_at")?,
+        })
+    }
+}
+
+impl SavedView {
+    /// Stored filters as query string pairs, non-string values use their JSON text
+    pub fn query_pairs(&self) -> Vec<(String, String)> {
+        self.params
+            .iter()
+            .filter(|(_, v)| !v.is_null())
+            .map(|(k, v)| {
+                let value = match v {
+                    serde_json::Value::String(s) => s.clone(),
+                    other => other.to_string(),
+                };
+                (k.clone(), value)
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub(crate) struct SavedViewRequest {
+    pub name: String,
+    pub endpoint: String,
+    #[serde(default)]
+    pub params: BTreeMap<String, serde_json::Value>,
+    // Defaults to the authenticated caller
+    #[serde(default)]
+    pub owner: String,
+}
+
+#[derive(Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub(crate) struct ViewListRequest {
+    pub owner: Option<String>,
+    pub endpoint: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub(crate) struct SavedViewResponse {
+    pub response: Vec<SavedView>,
+}
+
+impl IntoResponse for SavedViewResponse {
+    fn into_response(self) -> axum::response::Response {
+        let body = Json(json!({
+            "views": self
+        }));
+
+        (StatusCode::OK, body).into_response()
+    }
+}
@@ -3,21 +3,24 @@ use axum::response::IntoResponse;
 use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
+use utoipa::{IntoParams, ToSchema};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct WatermarkRequest {
     pub from: NaiveDate,
     pub to: NaiveDate,
+    pub account_id: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct WatermarkDataPoint {
     pub x: String,           // Time period label
     pub y: String,           // Watermark range label
     pub value: i32,          // Count of trades in this bucket
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct WatermarkResponse {
     pub data: Vec<WatermarkDataPoint>,
     pub min_watermark: f64,
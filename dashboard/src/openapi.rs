@@ -0,0 +1,400 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use axum::{Json, Router, extract::DefaultBodyLimit, routing::get};
+use utoipa::{
+    Modify, OpenApi, ToSchema,
+    openapi::{
+        self, Array, ContentBuilder, Deprecated, ObjectBuilder, Ref, RefOr, ResponseBuilder, Schema, Type,
+        security::{ApiKey, ApiKeyValue, HttpAuthScheme, HttpBuilder, SecurityScheme},
+    },
+};
+use utoipa_axum::{router::OpenApiRouter, routes};
+use utoipa_scalar::{Scalar, Servable};
+
+use crate::{
+    AppState, auth,
+    models::{
+        account::AccountResponse,
+        audit::AuditResponse,
+        benchmark::BenchmarkResponse,
+        correlation::CorrelationResponse,
+        distribution::DistributionResponse,
+        fees::FeeReport,
+        journal::JournalResponse,
+        metrics::{MetricsComparison, MetricsResponseBody},
+        performance::PerformanceResponse,
+        position::PositionsResponse,
+        risk_report::RiskReport,
+        strategy::StrategyResponse,
+        symbol::SymbolResponse,
+        view::SavedViewResponse,
+        watermark::WatermarkDataPoint,
+    },
+    service::{
+        self,
+        common::PROBLEM_CONTENT_TYPE,
+        envelope::{JSON_CONTENT_TYPE, Meta, Unwrap, UnwrapRules, V1_PREFIX, rule_key},
+    },
+};
+
+// Each model's IntoResponse nests the body under its own key, these mirror
+// that outer object so clients see the shape actually on the wire
+macro_rules! envelope {
+    ($name:ident, $key:ident, $body:ty) => {
+        #[derive(ToSchema)]
+        #[allow(dead_code)]
+        pub(crate) struct $name {
+            $key: $body,
+        }
+    };
+}
+
+envelope!(SymbolsBody, symbols, SymbolResponse);
+envelope!(StrategiesBody, strategies, StrategyResponse);
+envelope!(PerformanceBody, performance, PerformanceResponse);
+envelope!(PositionsBody, positions, PositionsResponse);
+envelope!(RiskBody, risk, RiskReport);
+envelope!(AccountBody, account, AccountResponse);
+envelope!(BenchmarkBody, benchmark, BenchmarkResponse);
+envelope!(CorrelationBody, correlation, CorrelationResponse);
+envelope!(DistributionBody, distribution, DistributionResponse);
+envelope!(FeesBody, fees, FeeReport);
+envelope!(JournalBody, journal, JournalResponse);
+envelope!(ViewsBody, views, SavedViewResponse);
+envelope!(AuditBody, audit, AuditResponse);
+
+#[derive(ToSchema)]
+#[allow(dead_code)]
+pub(crate) struct MetricsBody {
+    metrics: MetricsResponseBody,
+    // Only with compare_* parameters
+    comparison: Option<MetricsComparison>,
+    // Only with per_account=true, keyed by account id
+    accounts: Option<BTreeMap<String, MetricsResponseBody>>,
+}
+
+#[derive(ToSchema)]
+#[allow(dead_code)]
+pub(crate) struct WatermarksBody {
+    watermarks: Vec<WatermarkDataPoint>,
+    min_watermark: f64,
+    max_watermark: f64,
+}
+
+/// RFC 7807 problem details returned by every error, see `AppError`
+#[derive(ToSchema)]
+#[allow(dead_code)]
+pub(crate) struct Problem {
+    r#type: String,
+    title: String,
+    status: u16,
+    detail: String,
+    // Stable, e.g. invalid_date_range or range_too_large
+    code: String,
+}
+
+struct Security;
+
+impl Modify for Security {
+    fn modify(&self, openapi: &mut openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "bearer",
+            SecurityScheme::Http(HttpBuilder::new().scheme(HttpAuthScheme::Bearer).bearer_format("JWT").build()),
+        );
+        components.add_security_scheme(
+            "api_key",
+            SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::new(auth::API_KEY_HEADER))),
+        );
+    }
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "Data Viewer API", description = "Trading analytics served by the dashboard"),
+    modifiers(&Security),
+    security(("bearer" = []), ("api_key" = [])),
+    components(schemas(Problem, Meta)),
+)]
+struct ApiDoc;
+
+/// Routes and the OpenAPI document describing them, built from the same
+/// `#[utoipa::path]` annotations so neither can be registered without the other
+pub(crate) struct ApiRoutes {
+    // Layered with authentication and auditing by the caller
+    pub protected: Router<Arc<AppState>>,
+    pub public: Router<Arc<AppState>>,
+    pub spec: openapi::OpenApi,
+    // How the /api/v1 envelope finds the data in each protected route's body
+    pub unwrap: UnwrapRules,
+}
+
+pub(crate) fn routes(max_attachment_bytes: usize) -> ApiRoutes {
+    let (schemas, paths, upload) = routes!(service::journal::upload_attachment);
+    let upload = upload.layer(DefaultBodyLimit::max(max_attachment_bytes));
+
+    let (protected, mut spec) = OpenApiRouter::with_openapi(ApiDoc::openapi())
+        .routes(routes!(service::symbols::symbols))
+        .routes(routes!(service::strategy::strategy))
+        .routes(routes!(service::universe::universe))
+        .routes(routes!(service::performance::performance))
+        .routes(routes!(service::positions::positions))
+        .routes(routes!(service::risk::risk))
+        .routes(routes!(service::account::account))
+        .routes(routes!(service::benchmark::benchmark))
+        .routes(routes!(service::correlation::correlation))
+        .routes(routes!(service::distribution::distribution))
+        .routes(routes!(service::fees::fees))
+        .routes(routes!(service::metrics::metrics))
+        .routes(routes!(service::watermarks::watermarks))
+        .routes(routes!(service::journal::list))
+        .routes(routes!(
+            service::journal::get,
+            service::journal::create,
+            service::journal::update,
+            service::journal::delete
+        ))
+        .routes((schemas, paths, upload))
+        .routes(routes!(service::views::list, service::views::create))
+        .routes(routes!(service::views::get, service::views::update, service::views::delete))
+        .routes(routes!(service::events::events_sse))
+        .routes(routes!(service::events::events_ws))
+        .routes(routes!(service::audit::audit_log))
+        .routes(routes!(service::health::details))
+        .split_for_parts();
+
+    let (public, mut public_spec) = OpenApiRouter::new()
+        .routes(routes!(service::health::health))
+        .routes(routes!(service::health::livez))
+        .routes(routes!(service::health::readyz))
+        .split_for_parts();
+
+    with_problem_responses(&mut spec);
+    let unwrap = versioned(&mut spec);
+    with_problem_responses(&mut public_spec);
+    spec.merge(public_spec);
+
+    ApiRoutes { protected, public, spec, unwrap }
+}
+
+// Every operation can fail with problem details, documented once here rather
+// than on each handler
+fn with_problem_responses(spec: &mut openapi::OpenApi) {
+    let problem = ResponseBuilder::new()
+        .description("Problem details, `code` identifies the error")
+        .content(
+            PROBLEM_CONTENT_TYPE,
+            ContentBuilder::new().schema(Some(openapi::Ref::from_schema_name("Problem"))).build(),
+        )
+        .build();
+
+    for item in spec.paths.paths.values_mut() {
+        for operation in [
+            &mut item.get,
+            &mut item.put,
+            &mut item.post,
+            &mut item.delete,
+        ]
+        .into_iter()
+        .flatten()
+        {
+            operation
+                .responses
+                .responses
+                .entry("default".to_string())
+                .or_insert_with(|| RefOr::T(problem.clone()));
+        }
+    }
+}
+
+fn enveloped(data: RefOr<Schema>) -> RefOr<Schema> {
+    ObjectBuilder::new()
+        .property("data", data)
+        .required("data")
+        .property("meta", Ref::from_schema_name("Meta"))
+        .required("meta")
+        .property("errors", Array::new(Ref::from_schema_name("Problem")))
+        .required("errors")
+        .into()
+}
+
+fn sole_property<'a>(
+    schema: &'a RefOr<Schema>,
+    schemas: &'a BTreeMap<String, RefOr<Schema>>,
+) -> Option<(&'a String, &'a RefOr<Schema>)> {
+    let schema = match schema {
+        RefOr::Ref(r) => schemas.get(r.ref_location.trim_start_matches("#/components/schemas/"))?,
+        inline => inline,
+    };
+    match schema {
+        RefOr::T(Schema::Object(object)) if object.properties.len() == 1 => object.properties.iter().next(),
+        _ => None,
+    }
+}
+
+// {"symbols": {"response": [..]}} carries a list, {"benchmark": {..}} an
+// object and {"metrics", "comparison"} is taken whole
+fn unwrap_of(schema: &RefOr<Schema>, schemas: &BTreeMap<String, RefOr<Schema>>) -> (Unwrap, RefOr<Schema>) {
+    let Some((key, inner)) = sole_property(schema, schemas) else {
+        return (Unwrap::default(), schema.clone());
+    };
+    match sole_property(inner, schemas) {
+        Some((list, data)) if list == "response" => (Unwrap { key: Some(key.clone()), response: true }, data.clone()),
+        _ => (Unwrap { key: Some(key.clone()), response: false }, inner.clone()),
+    }
+}
+
+// Adds an /api/v1 copy of every operation answering in the envelope, marks
+// the originals deprecated and returns the unwrap rule of each copy
+fn versioned(spec: &mut openapi::OpenApi) -> UnwrapRules {
+    let schemas = spec.components.as_ref().map(|c| c.schemas.clone()).unwrap_or_default();
+    let mut rules = UnwrapRules::new();
+    let mut copies = BTreeMap::new();
+
+    for (path, item) in spec.paths.paths.iter_mut() {
+        let mut copy = item.clone();
+        let methods = [
+            ("GET", &mut item.get, &mut copy.get),
+            ("PUT", &mut item.put, &mut copy.put),
+            ("POST", &mut item.post, &mut copy.post),
+            ("DELETE", &mut item.delete, &mut copy.delete),
+        ];
+        for (method, legacy, current) in methods {
+            let (Some(legacy), Some(current)) = (legacy, current) else {
+                continue;
+            };
+            legacy.deprecated = Some(Deprecated::True);
+            current.operation_id = current.operation_id.as_ref().map(|id| format!("{id}_v1"));
+
+            for (status, response) in current.responses.responses.iter_mut() {
+                let RefOr::T(response) = response else {
+                    continue;
+                };
+                if let Some(content) = response.content.get_mut(JSON_CONTENT_TYPE)
+                    && let Some(schema) = &content.schema
+                {
+                    let (unwrap, data) = unwrap_of(schema, &schemas);
+                    if status.starts_with('2') {
+                        rules.insert(rule_key(method, path), unwrap);
+                    }
+                    content.schema = Some(enveloped(data));
+                }
+                if response.content.shift_remove(PROBLEM_CONTENT_TYPE).is_some() {
+                    response.description = "Problem details in errors, data is null".to_string();
+                    let null = ObjectBuilder::new().schema_type(Type::Null).into();
+                    response.content.insert(
+                        JSON_CONTENT_TYPE.to_string(),
+                        ContentBuilder::new().schema(Some(enveloped(null))).build(),
+                    );
+                }
+            }
+        }
+        copies.insert(format!("{V1_PREFIX}{path}"), copy);
+    }
+
+    spec.paths.paths.extend(copies);
+    rules
+}
+
+/// Serves the document at /openapi.json and a Scalar UI over it at /docs
+pub(crate) fn docs(spec: openapi::OpenApi) -> Router<Arc<AppState>> {
+    let json = spec.clone();
+    Router::new()
+        .route("/openapi.json", get(move || std::future::ready(Json(json.clone()))))
+        .merge(Scalar::with_url("/docs", spec))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COMMITTED_SPEC: &str = include_str!("../openapi.json");
+
+    fn generated() -> String {
+        routes(usize::MAX).spec.to_pretty_json().unwrap() + "\n"
+    }
+
+    // Regenerate with dashboard/scripts/generate-client.sh and commit dashboard/openapi.json
+    // and the frontend client alongside the handler change
+    #[test]
+    fn test_spec_matches_committed_document() {
+        let spec = generated();
+        if std::env::var_os("UPDATE_OPENAPI").is_some() {
+            std::fs::write(concat!(env!("CARGO_MANIFEST_DIR"), "/openapi.json"), &spec).unwrap();
+            return;
+        }
+        assert!(
+            spec == COMMITTED_SPEC,
+            "dashboard/openapi.json is out of date with the handlers, rerun with UPDATE_OPENAPI=1"
+        );
+    }
+
+    #[test]
+    fn test_client_matches_committed_files() {
+        let spec: serde_json::Value = serde_json::from_str(&generated()).unwrap();
+        let files = [
+            (crate::client::TYPES_FILE, crate::client::render_types(&spec)),
+            (crate::client::CLIENT_FILE, crate::client::render_client(&spec)),
+        ];
+        for (file, rendered) in files {
+            let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join(file);
+            if std::env::var_os("UPDATE_OPENAPI").is_some() {
+                std::fs::write(&path, &rendered).unwrap();
+                continue;
+            }
+            let committed = std::fs::read_to_string(&path).unwrap_or_default();
+            assert!(committed == rendered, "dashboard/{file} is out of date with the spec, rerun with UPDATE_OPENAPI=1");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_envelope_keys_match_responses() {
+        use axum::response::{IntoResponse, Response};
+        use crate::models::watermark::WatermarkResponse;
+
+        let spec = routes(usize::MAX).spec;
+        let schemas = &spec.components.as_ref().unwrap().schemas;
+        let documented = |name: &str| -> Vec<String> {
+            match &schemas[name] {
+                RefOr::T(openapi::Schema::Object(object)) => object.properties.keys().cloned().collect(),
+                _ => panic!("{name} is not an object schema"),
+            }
+        };
+        let written = |response: Response| async move {
+            let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            let body: BTreeMap<String, serde_json::Value> = serde_json::from_slice(&bytes).unwrap();
+            body.into_keys().collect::<Vec<_>>()
+        };
+
+        let cases = [
+            ("SymbolsBody", SymbolResponse { response: vec![] }.into_response()),
+            ("StrategiesBody", StrategyResponse { response: vec![] }.into_response()),
+            ("PerformanceBody", PerformanceResponse { response: vec![] }.into_response()),
+            ("AccountBody", AccountResponse { response: vec![] }.into_response()),
+            ("JournalBody", JournalResponse { response: vec![] }.into_response()),
+            ("ViewsBody", SavedViewResponse { response: vec![] }.into_response()),
+            ("AuditBody", AuditResponse { response: vec![] }.into_response()),
+            (
+                "WatermarksBody",
+                WatermarkResponse { data: vec![], min_watermark: 0.0, max_watermark: 0.0 }.into_response(),
+            ),
+        ];
+        for (name, response) in cases {
+            assert_eq!(documented(name), written(response).await, "{name}");
+        }
+    }
+
+    #[test]
+    fn test_v1_unwrap_rules_follow_documented_bodies() {
+        let rules = routes(usize::MAX).unwrap;
+        let rule = |method: &str, route: &str| rules.get(&rule_key(method, route)).cloned();
+        let keyed = |key: &str, response: bool| Some(Unwrap { key: Some(key.to_string()), response });
+
+        assert_eq!(rule("GET", "/symbols"), keyed("symbols", true));
+        assert_eq!(rule("POST", "/journal/{local_id}"), keyed("journal", true));
+        assert_eq!(rule("GET", "/benchmark"), keyed("benchmark", false));
+        assert_eq!(rule("GET", "/metrics"), Some(Unwrap::default()));
+        assert_eq!(rule("GET", "/events"), None);
+    }
+}
@@ -0,0 +1,48 @@
+use anyhow::{Context, Result, bail};
+use common::db_client::DBClient;
+use tracing::info;
+
+// Tables the dashboard owns itself, the strategy table is managed by the trading service
+const MIGRATIONS: &[(&str, &str)] = &[
+    ("journal", include_str!("../deploy/sql/journal.sql")),
+    ("saved_view", include_str!("../deploy/sql/saved_views.sql")),
+    ("audit_log", include_str!("../deploy/sql/audit_log.sql")),
+];
+
+pub(crate) async fn ensure(db: &DBClient) -> Result<()> {
+    for (name, sql) in MIGRATIONS {
+        sqlx::raw_sql(sql)
+            .execute(&db.pool)
+            .await
+            .context(format!("Failed to apply {name} schema"))?;
+        info!("Schema {name} is up to date");
+    }
+    Ok(())
+}
+
+/// Checks a table name taken from settings before it is spliced into SQL, allows an
+/// optional schema prefix such as `market.fx_rates`
+pub(crate) fn table_name(name: &str) -> Result<&str> {
+    let mut chars = name.chars();
+    let valid = chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.');
+    if !valid {
+        bail!("Invalid table name {name:?}, expected letters, digits, underscores and dots");
+    }
+    Ok(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_table_name_rejects_anything_but_identifiers() {
+        assert!(table_name("fx_rates").is_ok());
+        assert!(table_name("market.fx_rates").is_ok());
+        assert!(table_name("").is_err());
+        assert!(table_name("1rates").is_err());
+        assert!(table_name("rates; DROP TABLE strategy").is_err());
+        assert!(table_name("\"rates\"").is_err());
+    }
+}
@@ -0,0 +1,90 @@
+use axum::{extract::State, response::IntoResponse};
+use chrono::NaiveDate;
+use sqlx::types::Json;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use crate::{
+    AppState,
+    models::account::{AccountDailySnapshot, AccountResponse, AccountSeries},
+};
+
+use crate::openapi::AccountBody;
+use super::common::{AppError, SimpleRequest};
+use super::extract::ApiQuery;
+use crate::telemetry::ObserveQuery;
+
+// Latest snapshot per account and day, grouped by account and sorted by date
+pub(crate) async fn fetch_snapshots(
+    state: &AppState,
+    from: NaiveDate,
+    to: NaiveDate,
+    account_id: Option<&str>,
+) -> Result<BTreeMap<String, Vec<AccountDailySnapshot>>, AppError> {
+    // Every strategy row carries the snapshot of the day it was written, keep the
+    // most recently exited one per account and day
+    let query = r#"
+    SELECT DISTINCT ON (account->>'account_id', (account->>'date')::date)
+        account
+    FROM
+        strategy
+    WHERE
+        account IS NOT NULL
+    AND (account->>'date')::date >= $1
+    AND (account->>'date')::date <= $2
+    AND ($3::text IS NULL OR account->>'account_id' = $3)
+    ORDER BY
+        account->>'account_id',
+        (account->>'date')::date,
+        exit_time DESC
+    "#;
+
+    let rows = sqlx::query_scalar::<_, Json<AccountDailySnapshot>>(query)
+        .bind(from)
+        .bind(to)
+        .bind(account_id)
+        .fetch_all(&state.db.pool)
+        .observe("account_snapshots")
+        .await
+        .map_err(AppError::DatabaseError)?;
+
+    let mut by_account: BTreeMap<String, Vec<AccountDailySnapshot>> = BTreeMap::new();
+    for Json(snapshot) in rows {
+        by_account.entry(snapshot.account_id.clone()).or_default().push(snapshot);
+    }
+    for snapshots in by_account.values_mut() {
+        snapshots.sort_by_key(|s| s.date);
+        snapshots.dedup_by_key(|s| s.date);
+    }
+    Ok(by_account)
+}
+
+#[utoipa::path(
+    get,
+    path = "/account",
+    tag = "account",
+    params(SimpleRequest),
+    responses((status = 200, description = "Daily NLV with cash flow attribution per account, tags is rejected", body = AccountBody)),
+)]
+pub(crate) async fn account(
+    ApiQuery(request): ApiQuery<SimpleRequest>,
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    // Snapshots belong to accounts rather than strategies, so journal tags cannot narrow them
+    if request.tags.is_some() {
+        return AppError::BadRequest("tags does not apply to /account".to_string()).into_response();
+    }
+    let result = fetch_snapshots(&state, request.from, request.to, request.account_id.as_deref()).await;
+
+    match result {
+        Ok(by_account) => {
+            let response = by_account
+                .values()
+                .filter_map(|snapshots| AccountSeries::from_snapshots(snapshots, &state.calendar))
+                .collect();
+
+            AccountResponse { response }.into_response()
+        }
+        Err(e) => e.into_response(),
+    }
+}
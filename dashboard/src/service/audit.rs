@@ -0,0 +1,232 @@
+use axum::{
+    body::HttpBody,
+    extract::{MatchedPath, Request, State},
+    http::header,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use chrono::Utc;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info, warn};
+
+use crate::{
+    AppState,
+    auth::{AdminOnly, Principal},
+    models::audit::{AuditRecord, AuditRequest, AuditResponse},
+};
+
+use crate::openapi::AuditBody;
+use super::common::AppError;
+use super::envelope::Pagination;
+use super::extract::ApiQuery;
+use crate::telemetry::ObserveQuery;
+
+pub(crate) const AUDIT_TARGET: &str = "audit";
+
+const DEFAULT_LIMIT: i64 = 100;
+const MAX_LIMIT: i64 = 1000;
+
+// Never persisted, the values are credentials
+const REDACTED_PARAMS: &[&str] = &["access_token", "api_key"];
+
+fn query_params(query: Option<&str>) -> BTreeMap<String, String> {
+    serde_urlencoded::from_str::<Vec<(String, String)>>(query.unwrap_or_default())
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(k, v)| {
+            let v = if REDACTED_PARAMS.contains(&k.as_str()) { "[redacted]".to_string() } else { v };
+            (k, v)
+        })
+        .collect()
+}
+
+fn response_bytes(response: &Response) -> Option<i64> {
+    response
+        .headers()
+        .get(header::CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse().ok())
+        .or_else(|| response.body().size_hint().exact())
+        .map(|bytes| bytes as i64)
+}
+
+/// Records who accessed which route, layered outside `auth::authenticate` and the
+/// request timeout so rejected and timed out requests are recorded too
+pub(crate) async fn audit(State(state): State<Arc<AppState>>, request: Request, next: Next) -> Response {
+    let Some(sender) = &state.audit else {
+        return next.run(request).await;
+    };
+
+    let started = Instant::now();
+    let occurred_at = Utc::now();
+    let method = request.method().to_string();
+    let path = request.uri().path().to_string();
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|m| m.as_str().to_string())
+        .unwrap_or_else(|| path.clone());
+    let query = query_params(request.uri().query());
+
+    let response = next.run(request).await;
+
+    let principal = response.extensions().get::<Principal>();
+    let record = AuditRecord {
+        id: 0,
+        occurred_at,
+        subject: principal.map(|p| p.subject.clone()),
+        role: principal.map(|p| json_name(&p.role)),
+        auth_method: principal.map(|p| json_name(&p.method)),
+        method,
+        route,
+        path,
+        query,
+        status: response.status().as_u16() as i32,
+        response_bytes: response_bytes(&response),
+        duration_ms: started.elapsed().as_millis() as i64,
+    };
+    if let Err(e) = sender.try_send(record) {
+        warn!("Audit queue full or closed, dropping record: {e}");
+    }
+
+    response
+}
+
+fn json_name<T: serde::Serialize>(value: &T) -> String {
+    serde_json::to_value(value)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+async fn write_record(state: &AppState, record: &AuditRecord) {
+    if state.settings.audit.cloudwatch {
+        info!(target: AUDIT_TARGET, "{}", serde_json::json!({ "audit": record }));
+    }
+
+    let query = r#"
+    INSERT INTO audit_log (
+        occurred_at, subject, role, auth_method, method, route, path, query, status, response_bytes, duration_ms
+    )
+    VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+    "#;
+
+    let result = sqlx::query(query)
+        .bind(record.occurred_at)
+        .bind(&record.subject)
+        .bind(&record.role)
+        .bind(&record.auth_method)
+        .bind(&record.method)
+        .bind(&record.route)
+        .bind(&record.path)
+        .bind(sqlx::types::Json(&record.query))
+        .bind(record.status)
+        .bind(record.response_bytes)
+        .bind(record.duration_ms)
+        .execute(&state.db.pool)
+        .observe("audit_insert")
+        .await;
+
+    if let Err(e) = result {
+        error!("Failed to write audit record for {} {}: {e}", record.method, record.path);
+    }
+}
+
+/// Drains the audit queue into Postgres, flushing what is queued on shutdown
+pub(crate) async fn write_audit_log(
+    state: Arc<AppState>,
+    mut receiver: mpsc::Receiver<AuditRecord>,
+    cancel_token: CancellationToken,
+) {
+    loop {
+        let record = tokio::select! {
+            _ = cancel_token.cancelled() => break,
+            record = receiver.recv() => record,
+        };
+        match record {
+            Some(record) => write_record(&state, &record).await,
+            None => return,
+        }
+    }
+
+    receiver.close();
+    while let Some(record) = receiver.recv().await {
+        write_record(&state, &record).await;
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/audit",
+    tag = "admin",
+    params(AuditRequest),
+    responses((status = 200, description = "Audit records newest first, admin only", body = AuditBody)),
+)]
+pub(crate) async fn audit_log(
+    _admin: AdminOnly,
+    ApiQuery(request): ApiQuery<AuditRequest>,
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    let limit = request.limit.unwrap_or(DEFAULT_LIMIT);
+    if !(1..=MAX_LIMIT).contains(&limit) {
+        return AppError::BadRequest(format!("limit must be between 1 and {MAX_LIMIT}")).into_response();
+    }
+
+    let query = r#"
+    SELECT
+        *
+    FROM
+        audit_log
+    WHERE
+        (occurred_at AT TIME ZONE 'UTC')::date >= $1
+    AND (occurred_at AT TIME ZONE 'UTC')::date <= $2
+    AND ($3::text IS NULL OR subject = $3)
+    AND ($4::text IS NULL OR route = $4)
+    AND ($5::int IS NULL OR status = $5)
+    AND ($6::bigint IS NULL OR id < $6)
+    ORDER BY
+        id DESC
+    LIMIT $7
+    "#;
+
+    let result = sqlx::query_as::<_, AuditRecord>(query)
+        .bind(request.from)
+        .bind(request.to)
+        .bind(&request.subject)
+        .bind(&request.route)
+        .bind(request.status)
+        .bind(request.before_id)
+        .bind(limit)
+        .fetch_all(&state.db.pool)
+        .observe("audit_list")
+        .await
+        .map_err(AppError::DatabaseError);
+
+    match result {
+        Ok(rows) => {
+            // A full page may have more behind it
+            let next_before_id = if rows.len() as i64 == limit { rows.last().map(|r| r.id) } else { None };
+            let mut response = AuditResponse { response: rows }.into_response();
+            response.extensions_mut().insert(Pagination { limit, next_before_id });
+            response
+        }
+        Err(e) => e.into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_credentials_in_query_are_redacted() {
+        let params = query_params(Some("from=2025-01-01&access_token=secret&tags=a%2Cb"));
+        assert_eq!(params.get("access_token").map(String::as_str), Some("[redacted]"));
+        assert_eq!(params.get("tags").map(String::as_str), Some("a,b"));
+        assert_eq!(params.len(), 3);
+    }
+}
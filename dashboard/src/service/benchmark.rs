@@ -0,0 +1,239 @@
+use axum::{extract::State, response::IntoResponse};
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use crate::{
+    AppState,
+    calendar::TRADING_DAYS_PER_YEAR,
+    models::{
+        benchmark::{BenchmarkPoint, BenchmarkRequest, BenchmarkResponse, BenchmarkStats},
+        metrics::BASE_CAPITAL,
+        strategy::{Status, Strategy},
+    },
+};
+
+use crate::openapi::BenchmarkBody;
+use super::common::AppError;
+use super::metrics::daily_from_rows;
+use super::extract::ApiQuery;
+use crate::telemetry::ObserveQuery;
+
+struct AlignedDay {
+    strategy_return: f64,
+    benchmark_return: f64,
+}
+
+// Pair each benchmark session with the strategy P&L booked since the previous
+// session, so weekend and holiday exits land on the next trading day
+fn align(daily: &BTreeMap<NaiveDate, Decimal>, closes: &BTreeMap<NaiveDate, Decimal>) -> Vec<AlignedDay> {
+    let mut aligned = Vec::with_capacity(closes.len());
+    let mut sessions = closes.iter();
+    let Some((mut prev_date, mut prev_close)) = sessions.next() else { return aligned };
+
+    for (date, close) in sessions {
+        let pnl: Decimal = daily
+            .range(prev_date.succ_opt().unwrap_or(*prev_date)..=*date)
+            .map(|(_, v)| *v)
+            .sum();
+        let benchmark_return = if prev_close.is_zero() {
+            0.0
+        } else {
+            (*close / *prev_close - Decimal::ONE).to_f64().unwrap_or(0.0)
+        };
+        aligned.push(AlignedDay {
+            strategy_return: pnl.to_f64().unwrap_or(0.0) / BASE_CAPITAL,
+            benchmark_return,
+        });
+        prev_date = date;
+        prev_close = close;
+    }
+    aligned
+}
+
+fn mean(values: &[f64]) -> f64 {
+    values.iter().sum::<f64>() / values.len() as f64
+}
+
+fn capture(pairs: &[(f64, f64)], up: bool) -> Option<f64> {
+    let selected: Vec<(f64, f64)> = pairs
+        .iter()
+        .copied()
+        .filter(|(_, b)| if up { *b > 0.0 } else { *b < 0.0 })
+        .collect();
+    if selected.is_empty() {
+        return None;
+    }
+    let s_mean = selected.iter().map(|(s, _)| s).sum::<f64>() / selected.len() as f64;
+    let b_mean = selected.iter().map(|(_, b)| b).sum::<f64>() / selected.len() as f64;
+    if b_mean != 0.0 { Some(s_mean / b_mean) } else { None }
+}
+
+fn compute_benchmark_stats(aligned: &[AlignedDay]) -> BenchmarkStats {
+    let n = aligned.len();
+    if n < 2 {
+        return BenchmarkStats { sample_days: n, ..Default::default() };
+    }
+
+    let s: Vec<f64> = aligned.iter().map(|d| d.strategy_return).collect();
+    let b: Vec<f64> = aligned.iter().map(|d| d.benchmark_return).collect();
+    let (s_mean, b_mean) = (mean(&s), mean(&b));
+
+    let mut cov = 0.0_f64;
+    let mut s_var = 0.0_f64;
+    let mut b_var = 0.0_f64;
+    for (si, bi) in s.iter().zip(b.iter()) {
+        cov += (si - s_mean) * (bi - b_mean);
+        s_var += (si - s_mean) * (si - s_mean);
+        b_var += (bi - b_mean) * (bi - b_mean);
+    }
+    let denom = n as f64 - 1.0;
+    cov /= denom;
+    s_var /= denom;
+    b_var /= denom;
+
+    let beta = if b_var > 0.0 { Some(cov / b_var) } else { None };
+    let alpha_annual = beta.map(|beta| (s_mean - beta * b_mean) * TRADING_DAYS_PER_YEAR);
+    let correlation = if s_var > 0.0 && b_var > 0.0 { Some(cov / (s_var.sqrt() * b_var.sqrt())) } else { None };
+
+    let active: Vec<f64> = s.iter().zip(b.iter()).map(|(si, bi)| si - bi).collect();
+    let active_mean = mean(&active);
+    let active_var = active.iter().map(|a| (a - active_mean) * (a - active_mean)).sum::<f64>() / denom;
+    let tracking_error = if active_var > 0.0 { Some(active_var.sqrt() * TRADING_DAYS_PER_YEAR.sqrt()) } else { None };
+    let information_ratio = tracking_error.map(|te| active_mean * TRADING_DAYS_PER_YEAR / te);
+
+    let pairs: Vec<(f64, f64)> = s.into_iter().zip(b).collect();
+
+    BenchmarkStats {
+        beta,
+        alpha_annual,
+        correlation,
+        tracking_error,
+        information_ratio,
+        up_capture: capture(&pairs, true),
+        down_capture: capture(&pairs, false),
+        sample_days: n,
+    }
+}
+
+fn equity_curve(daily: &BTreeMap<NaiveDate, Decimal>, closes: &BTreeMap<NaiveDate, Decimal>) -> Vec<BenchmarkPoint> {
+    let base = Decimal::from_f64(BASE_CAPITAL).unwrap_or(Decimal::ZERO);
+    let Some((first_date, first_close)) = closes.iter().next() else { return Vec::new() };
+
+    let mut curve = Vec::with_capacity(closes.len());
+    let mut strategy_equity = Decimal::ZERO;
+    let mut last = *first_date;
+    for (date, close) in closes {
+        if date > first_date {
+            strategy_equity += daily
+                .range(last.succ_opt().unwrap_or(last)..=*date)
+                .map(|(_, v)| *v)
+                .sum::<Decimal>();
+        }
+        let benchmark_equity = if first_close.is_zero() {
+            Decimal::ZERO
+        } else {
+            base * (*close / *first_close - Decimal::ONE)
+        };
+        curve.push(BenchmarkPoint { date: *date, strategy_equity, benchmark_equity });
+        last = *date;
+    }
+    curve
+}
+
+#[utoipa::path(
+    get,
+    path = "/benchmark",
+    tag = "analytics",
+    params(BenchmarkRequest),
+    responses((status = 200, description = "Equity curve and statistics against the benchmark", body = BenchmarkBody)),
+)]
+pub(crate) async fn benchmark(
+    ApiQuery(request): ApiQuery<BenchmarkRequest>,
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    let Some(series) = &state.benchmark else {
+        return AppError::ServiceUnavailable("No benchmark is configured".to_string()).into_response();
+    };
+    let closes = series.closes_between(request.from, request.to);
+
+    let query = r#"
+    SELECT
+        *
+    FROM
+        strategy
+    WHERE
+        (exit_time AT TIME ZONE $5)::date >= $1
+    AND (exit_time AT TIME ZONE $5)::date <= $2
+    AND status = $3
+    AND ($4::text IS NULL OR account->>'account_id' = $4)
+    "#;
+
+    let result = sqlx::query_as::<_, Strategy>(query)
+        .bind(request.from)
+        .bind(request.to)
+        .bind(Into::<i32>::into(Status::Closed))
+        .bind(&request.account_id)
+        .bind(state.calendar.timezone_name())
+        .fetch_all(&state.db.pool)
+        .observe("benchmark_strategies")
+        .await
+        .map_err(AppError::DatabaseError)
+        .and_then(|mut rows| state.fx.to_reporting(&mut rows, &state.calendar).map(|_| rows));
+
+    match result {
+        Ok(rows) => {
+            let daily = daily_from_rows(request.from, request.to, &state.calendar, &rows);
+            let aligned = align(&daily, &closes);
+
+            BenchmarkResponse {
+                symbol: series.symbol.clone(),
+                from: request.from,
+                to: request.to,
+                stats: compute_benchmark_stats(&aligned),
+                curve: equity_curve(&daily, &closes),
+            }
+            .into_response()
+        }
+        Err(e) => e.into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(s: f64, b: f64) -> AlignedDay {
+        AlignedDay {
+            strategy_return: s,
+            benchmark_return: b,
+        }
+    }
+
+    #[test]
+    fn test_leveraged_benchmark_has_beta_two_and_full_correlation() {
+        let aligned = vec![day(0.02, 0.01), day(-0.04, -0.02), day(0.06, 0.03), day(0.0, 0.0)];
+        let stats = compute_benchmark_stats(&aligned);
+        assert!((stats.beta.unwrap() - 2.0).abs() < 1e-9);
+        assert!((stats.correlation.unwrap() - 1.0).abs() < 1e-9);
+        assert!(stats.alpha_annual.unwrap().abs() < 1e-9);
+        assert!((stats.up_capture.unwrap() - 2.0).abs() < 1e-9);
+        assert!((stats.down_capture.unwrap() - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_weekend_pnl_rolls_into_next_session() {
+        let d = |n| NaiveDate::from_ymd_opt(2025, 1, n).unwrap();
+        let daily: BTreeMap<NaiveDate, Decimal> =
+            [(d(3), Decimal::from(50)), (d(4), Decimal::from(25)), (d(5), Decimal::from(25))].into_iter().collect();
+        let closes: BTreeMap<NaiveDate, Decimal> =
+            [(d(3), Decimal::from(100)), (d(6), Decimal::from(101))].into_iter().collect();
+
+        let aligned = align(&daily, &closes);
+        assert_eq!(aligned.len(), 1);
+        assert!((aligned[0].strategy_return - 50.0 / BASE_CAPITAL).abs() < 1e-12);
+        assert!((aligned[0].benchmark_return - 0.01).abs() < 1e-12);
+    }
+}
@@ -1,30 +1,206 @@
-use axum::{http::StatusCode, response::IntoResponse, Json};
+use axum::{
+    Json,
+    http::{StatusCode, header},
+    response::IntoResponse,
+};
 use chrono::NaiveDate;
 use serde_json::json;
+use utoipa::IntoParams;
+use tracing::error;
 
-#[derive(serde::Deserialize, sqlx::Encode)]
+use crate::models::journal::normalize_tags;
+
+#[derive(serde::Deserialize, sqlx::Encode, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct SimpleRequest {
     pub from: NaiveDate,
     pub to: NaiveDate,
+    pub account_id: Option<String>,
+    // Comma separated journal tags, matches strategies tagged with any of them
+    pub tags: Option<String>,
+}
+
+#[derive(serde::Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct AccountFilter {
+    pub account_id: Option<String>,
 }
 
+pub const PROBLEM_CONTENT_TYPE: &str = "application/problem+json";
+
+// Postgres SQLSTATE for a statement cancelled by statement_timeout
+const QUERY_CANCELED: &str = "57014";
+
 pub enum AppError {
+    // Validation, all 400
+    BadRequest(String),
+    InvalidQuery(String),
+    InvalidBody(String),
+    InvalidPath(String),
+    UnknownValue(String),
+    InvalidDateRange { from: NaiveDate, to: NaiveDate },
+    RangeTooLarge { days: i64, max_days: i64 },
+
+    Unauthorized(String),
+    Forbidden(String),
+    NotFound(String),
+    Conflict(String),
+    PayloadTooLarge(String),
+    Timeout(String),
+    ServiceUnavailable(String),
+    // Logged in full, clients only see a generic detail
     DatabaseError(sqlx::Error),
+    Internal(String),
+}
+
+impl AppError {
+    /// Stable machine readable code, also the last segment of the problem type
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::BadRequest(_) => "invalid_parameter",
+            AppError::InvalidQuery(_) => "invalid_query",
+            AppError::InvalidBody(_) => "invalid_body",
+            AppError::InvalidPath(_) => "invalid_path",
+            AppError::UnknownValue(_) => "unknown_value",
+            AppError::InvalidDateRange { .. } => "invalid_date_range",
+            AppError::RangeTooLarge { .. } => "range_too_large",
+            AppError::Unauthorized(_) => "unauthorized",
+            AppError::Forbidden(_) => "forbidden",
+            AppError::NotFound(_) => "not_found",
+            AppError::Conflict(_) => "conflict",
+            AppError::PayloadTooLarge(_) => "payload_too_large",
+            AppError::Timeout(_) => "timeout",
+            AppError::ServiceUnavailable(_) => "service_unavailable",
+            AppError::DatabaseError(e) => match database_timeout(e) {
+                Some(_) => "timeout",
+                None => "internal",
+            },
+            AppError::Internal(_) => "internal",
+        }
+    }
+
+    pub fn status(&self) -> StatusCode {
+        match self {
+            AppError::BadRequest(_)
+            | AppError::InvalidQuery(_)
+            | AppError::InvalidBody(_)
+            | AppError::InvalidPath(_)
+            | AppError::UnknownValue(_)
+            | AppError::InvalidDateRange { .. }
+            | AppError::RangeTooLarge { .. } => StatusCode::BAD_REQUEST,
+            AppError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            AppError::Forbidden(_) => StatusCode::FORBIDDEN,
+            AppError::NotFound(_) => StatusCode::NOT_FOUND,
+            AppError::Conflict(_) => StatusCode::CONFLICT,
+            AppError::PayloadTooLarge(_) => StatusCode::PAYLOAD_TOO_LARGE,
+            AppError::Timeout(_) => StatusCode::GATEWAY_TIMEOUT,
+            AppError::ServiceUnavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
+            AppError::DatabaseError(e) => database_timeout(e).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR),
+            AppError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn title(&self) -> &'static str {
+        match self {
+            AppError::BadRequest(_) => "Invalid parameter",
+            AppError::InvalidQuery(_) => "Invalid query string",
+            AppError::InvalidBody(_) => "Invalid request body",
+            AppError::InvalidPath(_) => "Invalid path parameter",
+            AppError::UnknownValue(_) => "Unknown value",
+            AppError::InvalidDateRange { .. } => "Invalid date range",
+            AppError::RangeTooLarge { .. } => "Date range too large",
+            AppError::Unauthorized(_) => "Unauthorized",
+            AppError::Forbidden(_) => "Forbidden",
+            AppError::NotFound(_) => "Not found",
+            AppError::Conflict(_) => "Conflict",
+            AppError::PayloadTooLarge(_) => "Payload too large",
+            AppError::Timeout(_) => "Timed out",
+            AppError::ServiceUnavailable(_) => "Service unavailable",
+            AppError::DatabaseError(e) => match database_timeout(e) {
+                Some(_) => "Timed out",
+                None => "Internal error",
+            },
+            AppError::Internal(_) => "Internal error",
+        }
+    }
+
+    pub fn detail(&self) -> String {
+        match self {
+            AppError::BadRequest(message)
+            | AppError::InvalidQuery(message)
+            | AppError::InvalidBody(message)
+            | AppError::InvalidPath(message)
+            | AppError::UnknownValue(message)
+            | AppError::Unauthorized(message)
+            | AppError::Forbidden(message)
+            | AppError::NotFound(message)
+            | AppError::Conflict(message)
+            | AppError::PayloadTooLarge(message)
+            | AppError::Timeout(message)
+            | AppError::ServiceUnavailable(message) => message.clone(),
+            AppError::InvalidDateRange { from, to } => format!("from ({from}) is after to ({to})"),
+            AppError::RangeTooLarge { days, max_days } => {
+                format!("Range of {days} days exceeds the maximum of {max_days}")
+            }
+            AppError::DatabaseError(e) => match database_timeout(e) {
+                Some(StatusCode::SERVICE_UNAVAILABLE) => "Database is busy, retry shortly".to_string(),
+                Some(_) => "Database query timed out".to_string(),
+                None => "Internal error while querying the database".to_string(),
+            },
+            AppError::Internal(_) => "Internal error".to_string(),
+        }
+    }
+
+    // The detail sent to clients is redacted, the cause only goes to the logs
+    pub fn log(&self) {
+        match self {
+            AppError::DatabaseError(e) => error!("Database error: {e}"),
+            AppError::Internal(message) => error!("Internal error: {message}"),
+            _ => {}
+        }
+    }
+}
+
+// Timeouts are reported as such rather than as a generic 500
+fn database_timeout(e: &sqlx::Error) -> Option<StatusCode> {
+    match e {
+        sqlx::Error::PoolTimedOut => Some(StatusCode::SERVICE_UNAVAILABLE),
+        sqlx::Error::Database(db) if db.code().as_deref() == Some(QUERY_CANCELED) => Some(StatusCode::GATEWAY_TIMEOUT),
+        _ => None,
+    }
+}
+
+/// `value` with LIKE wildcards escaped, for `column LIKE $1 || '%'` prefix matches
+pub fn like_escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")
+}
+
+pub fn parse_tags(tags: &Option<String>) -> Option<Vec<String>> {
+    let tags = normalize_tags(tags.as_deref()?.split(','));
+    if tags.is_empty() { None } else { Some(tags) }
 }
 
+// RFC 7807 problem details, `code` is an extension member clients can match on
 impl IntoResponse for AppError {
     fn into_response(self) -> axum::response::Response {
-        let (status, error_message) = match self {
-            AppError::DatabaseError(e) => (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Database error: {e}"),
-            ),
-        };
+        self.log();
 
+        let status = self.status();
+        let code = self.code();
         let body = Json(json!({
-            "error": error_message,
+            "type": format!("/problems/{code}"),
+            "title": self.title(),
+            "status": status.as_u16(),
+            "detail": self.detail(),
+            "code": code,
         }));
 
-        (status, body).into_response()
+        let mut response = (status, [(header::CONTENT_TYPE, PROBLEM_CONTENT_TYPE)], body).into_response();
+        if status == StatusCode::UNAUTHORIZED {
+            response
+                .headers_mut()
+                .insert(header::WWW_AUTHENTICATE, header::HeaderValue::from_static("Bearer"));
+        }
+        response
     }
 }
@@ -0,0 +1,173 @@
+use axum::{extract::State, response::IntoResponse};
+use rust_decimal::prelude::ToPrimitive;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use crate::{
+    AppState,
+    models::{
+        correlation::{CorrelationGroup, CorrelationRequest, CorrelationResponse},
+        strategy::{Status, Strategy},
+    },
+};
+
+use crate::openapi::CorrelationBody;
+use super::common::AppError;
+use super::metrics::daily_from_rows;
+use super::extract::ApiQuery;
+use crate::telemetry::ObserveQuery;
+
+fn pearson(a: &[f64], b: &[f64]) -> Option<f64> {
+    let n = a.len() as f64;
+    if a.len() < 2 {
+        return None;
+    }
+    let a_mean = a.iter().sum::<f64>() / n;
+    let b_mean = b.iter().sum::<f64>() / n;
+    let mut cov = 0.0_f64;
+    let mut a_var = 0.0_f64;
+    let mut b_var = 0.0_f64;
+    for (ai, bi) in a.iter().zip(b.iter()) {
+        cov += (ai - a_mean) * (bi - b_mean);
+        a_var += (ai - a_mean) * (ai - a_mean);
+        b_var += (bi - b_mean) * (bi - b_mean);
+    }
+    if a_var > 0.0 && b_var > 0.0 { Some(cov / (a_var.sqrt() * b_var.sqrt())) } else { None }
+}
+
+fn correlation_matrix(series: &[Vec<f64>]) -> Vec<Vec<Option<f64>>> {
+    let n = series.len();
+    let mut matrix = vec![vec![None; n]; n];
+    for i in 0..n {
+        for j in i..n {
+            let c = pearson(&series[i], &series[j]);
+            matrix[i][j] = c;
+            matrix[j][i] = c;
+        }
+    }
+    matrix
+}
+
+// Average-linkage agglomerative clustering on 1 - correlation, returning the
+// dendrogram leaf order so strongly correlated series end up adjacent
+fn cluster_order(matrix: &[Vec<Option<f64>>]) -> Vec<usize> {
+    let distance = |i: usize, j: usize| 1.0 - matrix[i][j].unwrap_or(0.0);
+    let mut clusters: Vec<Vec<usize>> = (0..matrix.len()).map(|i| vec![i]).collect();
+
+    while clusters.len() > 1 {
+        let mut best = (0, 1, f64::INFINITY);
+        for a in 0..clusters.len() {
+            for b in (a + 1)..clusters.len() {
+                let total: f64 = clusters[a]
+                    .iter()
+                    .flat_map(|i| clusters[b].iter().map(move |j| (*i, *j)))
+                    .map(|(i, j)| distance(i, j))
+                    .sum();
+                let avg = total / (clusters[a].len() * clusters[b].len()) as f64;
+                if avg < best.2 {
+                    best = (a, b, avg);
+                }
+            }
+        }
+        let merged = clusters.remove(best.1);
+        clusters[best.0].extend(merged);
+    }
+
+    clusters.pop().unwrap_or_default()
+}
+
+#[utoipa::path(
+    get,
+    path = "/correlation",
+    tag = "analytics",
+    params(CorrelationRequest),
+    responses((status = 200, description = "Clustered correlation matrix of daily P&L", body = CorrelationBody)),
+)]
+pub(crate) async fn correlation(
+    ApiQuery(request): ApiQuery<CorrelationRequest>,
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    let query = r#"
+    SELECT
+        *
+    FROM
+        strategy
+    WHERE
+        (exit_time AT TIME ZONE $5)::date >= $1
+    AND (exit_time AT TIME ZONE $5)::date <= $2
+    AND status = $3
+    AND ($4::text IS NULL OR account->>'account_id' = $4)
+    "#;
+
+    let result = sqlx::query_as::<_, Strategy>(query)
+        .bind(request.from)
+        .bind(request.to)
+        .bind(Into::<i32>::into(Status::Closed))
+        .bind(&request.account_id)
+        .bind(state.calendar.timezone_name())
+        .fetch_all(&state.db.pool)
+        .observe("correlation")
+        .await
+        .map_err(AppError::DatabaseError)
+        .and_then(|mut rows| state.fx.to_reporting(&mut rows, &state.calendar).map(|_| rows));
+
+    match result {
+        Ok(rows) => {
+
+            let mut groups: BTreeMap<String, Vec<Strategy>> = BTreeMap::new();
+            for s in rows {
+                let key = match request.group_by {
+                    CorrelationGroup::Symbol => s.symbol.clone(),
+                    CorrelationGroup::StrategyType => s.meta.r#type.to_string(),
+                };
+                groups.entry(key).or_default().push(s);
+            }
+
+            let labels: Vec<String> = groups.keys().cloned().collect();
+            let series: Vec<Vec<f64>> = groups
+                .values()
+                .map(|rows| {
+                    daily_from_rows(request.from, request.to, &state.calendar, rows)
+                        .values()
+                        .map(|v| v.to_f64().unwrap_or(0.0))
+                        .collect()
+                })
+                .collect();
+            let sample_days = series.first().map(Vec::len).unwrap_or(0);
+
+            let matrix = correlation_matrix(&series);
+            let order = cluster_order(&matrix);
+
+            CorrelationResponse {
+                from: request.from,
+                to: request.to,
+                group_by: request.group_by,
+                labels: order.iter().map(|i| labels[*i].clone()).collect(),
+                matrix: order.iter().map(|i| order.iter().map(|j| matrix[*i][*j]).collect()).collect(),
+                sample_days,
+            }
+            .into_response()
+        }
+        Err(e) => e.into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cluster_order_keeps_correlated_series_adjacent() {
+        let series = vec![
+            vec![1.0, 2.0, 3.0, 4.0, 5.0],
+            vec![5.0, 1.0, 4.0, 1.0, 5.0],
+            vec![1.1, 2.1, 2.9, 4.2, 5.0],
+            vec![5.1, 0.9, 4.1, 1.2, 4.8],
+        ];
+        let order = cluster_order(&correlation_matrix(&series));
+        let pos = |i| order.iter().position(|x| *x == i).unwrap();
+        assert_eq!(order.len(), 4);
+        assert_eq!(pos(0).abs_diff(pos(2)), 1);
+        assert_eq!(pos(1).abs_diff(pos(3)), 1);
+    }
+}
@@ -0,0 +1,241 @@
+use axum::{extract::State, response::IntoResponse};
+use rust_decimal::prelude::ToPrimitive;
+use std::sync::Arc;
+
+use crate::{
+    AppState,
+    models::{
+        distribution::{BinMethod, DistributionRequest, DistributionResponse, HistogramBin, SeriesDistribution},
+        metrics::MetricsFilter,
+        strategy::Strategy,
+    },
+};
+
+use crate::openapi::DistributionBody;
+use super::common::AppError;
+use super::metrics::fetch_closed;
+use super::extract::ApiQuery;
+
+const DEFAULT_FIXED_BINS: usize = 20;
+const DEFAULT_QUANTILE_BINS: usize = 10;
+const MAX_BINS: usize = 200;
+
+// Linear interpolation between closest ranks, values must be sorted
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    let rank = p * (sorted.len() - 1) as f64;
+    let lower = rank.floor() as usize;
+    let upper = rank.ceil() as usize;
+    sorted[lower] + (sorted[upper] - sorted[lower]) * (rank - lower as f64)
+}
+
+// Counts per [edge_i, edge_i+1), the last bin also takes values equal to its upper edge
+fn count_bins(sorted: &[f64], edges: &[f64]) -> Vec<HistogramBin> {
+    edges
+        .windows(2)
+        .enumerate()
+        .map(|(i, edge)| {
+            let lo = sorted.partition_point(|v| *v < edge[0]);
+            let hi = if i == edges.len() - 2 { sorted.len() } else { sorted.partition_point(|v| *v < edge[1]) };
+            HistogramBin { lower: edge[0], upper: edge[1], count: hi - lo }
+        })
+        .collect()
+}
+
+fn equal_width_edges(min: f64, max: f64, count: usize) -> Vec<f64> {
+    let count = count.clamp(1, MAX_BINS);
+    let width = (max - min) / count as f64;
+    let mut edges: Vec<f64> = (0..count).map(|i| min + width * i as f64).collect();
+    edges.push(max);
+    edges
+}
+
+// Bins of exactly the requested width from min, the last one may run past max
+fn fixed_width_edges(min: f64, max: f64, width: f64) -> Result<Vec<f64>, AppError> {
+    let count = ((max - min) / width).ceil().max(1.0);
+    if count > MAX_BINS as f64 {
+        return Err(AppError::BadRequest(format!(
+            "bin_width {width} needs {count} bins over this range, at most {MAX_BINS} are allowed"
+        )));
+    }
+    Ok((0..=count as usize).map(|i| min + width * i as f64).collect())
+}
+
+fn histogram(
+    sorted: &[f64],
+    method: BinMethod,
+    bin_count: Option<usize>,
+    bin_width: Option<f64>,
+) -> Result<Vec<HistogramBin>, AppError> {
+    let (Some(&min), Some(&max)) = (sorted.first(), sorted.last()) else {
+        return Ok(Vec::new());
+    };
+    if min == max {
+        return Ok(vec![HistogramBin { lower: min, upper: max, count: sorted.len() }]);
+    }
+
+    let edges = match method {
+        BinMethod::Fixed => match bin_width {
+            Some(width) => fixed_width_edges(min, max, width)?,
+            None => equal_width_edges(min, max, bin_count.unwrap_or(DEFAULT_FIXED_BINS)),
+        },
+        BinMethod::Quantile => {
+            let count = bin_count.unwrap_or(DEFAULT_QUANTILE_BINS).clamp(1, MAX_BINS);
+            let mut edges: Vec<f64> = (0..=count).map(|i| percentile(sorted, i as f64 / count as f64)).collect();
+            // Heavy ties collapse neighbouring quantiles into the same edge
+            edges.dedup();
+            edges
+        }
+        BinMethod::FreedmanDiaconis => {
+            let n = sorted.len() as f64;
+            let iqr = percentile(sorted, 0.75) - percentile(sorted, 0.25);
+            let width = 2.0 * iqr / n.cbrt();
+            let count = if width > 0.0 {
+                ((max - min) / width).ceil() as usize
+            } else {
+                n.log2().ceil() as usize + 1
+            };
+            equal_width_edges(min, max, count)
+        }
+    };
+
+    Ok(count_bins(sorted, &edges))
+}
+
+fn series_distribution(
+    mut values: Vec<f64>,
+    method: BinMethod,
+    bin_count: Option<usize>,
+    bin_width: Option<f64>,
+) -> Result<SeriesDistribution, AppError> {
+    values.retain(|v| v.is_finite());
+    values.sort_by(f64::total_cmp);
+    let count = values.len();
+    if count == 0 {
+        return Ok(SeriesDistribution::default());
+    }
+
+    let n = count as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    let moment = |k: i32| values.iter().map(|v| (v - mean).powi(k)).sum::<f64>() / n;
+    let (m2, m3, m4) = (moment(2), moment(3), moment(4));
+    let std_dev = (count > 1).then(|| (m2 * n / (n - 1.0)).sqrt());
+    let (skew, kurtosis) = if m2 > 0.0 {
+        (Some(m3 / m2.powf(1.5)), Some(m4 / (m2 * m2) - 3.0))
+    } else {
+        (None, None)
+    };
+
+    Ok(SeriesDistribution {
+        count,
+        mean: Some(mean),
+        std_dev,
+        min: values.first().copied(),
+        max: values.last().copied(),
+        skew,
+        kurtosis,
+        percentiles: (1..=99).map(|p| percentile(&values, p as f64 / 100.0)).collect(),
+        bins: histogram(&values, method, bin_count, bin_width)?,
+    })
+}
+
+fn rows_distribution(
+    rows: &[Strategy],
+    request: &DistributionRequest,
+    value: impl Fn(&Strategy) -> Option<f64>,
+) -> Result<SeriesDistribution, AppError> {
+    let values = rows.iter().filter_map(value).collect();
+    series_distribution(values, request.bins, request.bin_count, request.bin_width)
+}
+
+#[utoipa::path(
+    get,
+    path = "/distribution",
+    tag = "analytics",
+    params(DistributionRequest),
+    responses((status = 200, description = "Histograms, percentiles and moments per trade series", body = DistributionBody)),
+)]
+pub(crate) async fn distribution(
+    ApiQuery(request): ApiQuery<DistributionRequest>,
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    if request.bin_count.is_some_and(|c| c == 0 || c > MAX_BINS) {
+        return AppError::BadRequest(format!("bin_count must be between 1 and {MAX_BINS}")).into_response();
+    }
+    if request.bin_width.is_some_and(|w| !w.is_finite() || w <= 0.0) {
+        return AppError::BadRequest("bin_width must be positive".to_string()).into_response();
+    }
+
+    let filter = MetricsFilter {
+        from: request.from,
+        to: request.to,
+        account_id: request.account_id.clone(),
+        tags: request.tags.clone(),
+        strategy_type: request.strategy_type,
+    };
+
+    let rows = match fetch_closed(&state, &filter).await {
+        Ok(rows) => rows,
+        Err(e) => return e.into_response(),
+    };
+    match build_response(&rows, &request, state.fx.reporting_currency()) {
+        Ok(response) => response.into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+fn build_response(rows: &[Strategy], request: &DistributionRequest, currency: &str) -> Result<DistributionResponse, AppError> {
+    Ok(DistributionResponse {
+        from: request.from,
+        to: request.to,
+        currency: currency.to_string(),
+        bin_method: request.bins,
+        net_pnl: rows_distribution(rows, request, |s| (s.risk.stats.pnl - s.risk.stats.fee).to_f64())?,
+        roi: rows_distribution(rows, request, |s| s.risk.stats.roi.to_f64())?,
+        fees: rows_distribution(rows, request, |s| s.risk.stats.fee.to_f64())?,
+        holding_hours: rows_distribution(rows, request, |s| {
+            Some((s.exit_time - s.entry_time).num_seconds() as f64 / 3600.0)
+        })?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_histograms_cover_every_value_once() {
+        let values: Vec<f64> = (1..=100).map(|v| v as f64).collect();
+        for method in [BinMethod::Fixed, BinMethod::Quantile, BinMethod::FreedmanDiaconis] {
+            let bins = histogram(&values, method, Some(4), None).ok().unwrap();
+            assert_eq!(bins.iter().map(|b| b.count).sum::<usize>(), 100);
+            assert_eq!(bins.first().unwrap().lower, 1.0);
+            assert_eq!(bins.last().unwrap().upper, 100.0);
+        }
+        let quartiles = histogram(&values, BinMethod::Quantile, Some(4), None).ok().unwrap();
+        assert!(quartiles.iter().all(|b| b.count == 25));
+    }
+
+    #[test]
+    fn test_symmetric_series_has_no_skew() {
+        let series = series_distribution(vec![-2.0, -1.0, 0.0, 1.0, 2.0], BinMethod::Fixed, Some(5), None).ok().unwrap();
+        assert_eq!(series.percentiles.len(), 99);
+        assert_eq!(series.percentiles[49], 0.0);
+        assert!(series.skew.unwrap().abs() < 1e-12);
+        // m4 / m2^2 = 6.8 / 4 for this sample
+        assert!((series.kurtosis.unwrap() + 1.3).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_bin_width_is_kept_or_rejected() {
+        let values: Vec<f64> = (0..=10).map(|v| v as f64).collect();
+        let bins = histogram(&values, BinMethod::Fixed, None, Some(3.0)).ok().unwrap();
+        assert!(bins.iter().all(|b| b.upper - b.lower == 3.0));
+        assert_eq!(bins.last().unwrap().upper, 12.0);
+        assert_eq!(bins.iter().map(|b| b.count).sum::<usize>(), 11);
+
+        match histogram(&values, BinMethod::Fixed, None, Some(0.01)) {
+            Err(err) => assert_eq!(err.status(), axum::http::StatusCode::BAD_REQUEST),
+            Ok(_) => panic!("a width needing 1000 bins was accepted"),
+        }
+    }
+}
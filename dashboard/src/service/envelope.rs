@@ -0,0 +1,151 @@
+use axum::{
+    body::Body,
+    extract::{MatchedPath, Request, State},
+    http::{HeaderValue, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use serde::Serialize;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Instant;
+use utoipa::ToSchema;
+
+use super::common::{AppError, PROBLEM_CONTENT_TYPE};
+
+pub(crate) const V1_PREFIX: &str = "/api/v1";
+
+pub(crate) const JSON_CONTENT_TYPE: &str = "application/json";
+
+// RFC 9745 structured date, the legacy routes were deprecated on 2026-10-19
+const LEGACY_DEPRECATED_AT: &str = "@1792368000";
+
+#[derive(Serialize, Clone, Debug, PartialEq, ToSchema)]
+pub(crate) struct Pagination {
+    pub limit: i64,
+    // Pass as before_id for the next page, absent on the last page
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_before_id: Option<i64>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub(crate) struct Timings {
+    pub total_ms: f64,
+}
+
+#[derive(Serialize, ToSchema)]
+pub(crate) struct Meta {
+    // Items in data when it is a list
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub count: Option<usize>,
+    // Set by paged handlers through the response extensions
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pagination: Option<Pagination>,
+    pub timings: Timings,
+}
+
+/// Where a legacy body keeps its data, derived from the documented response
+/// schema by `openapi::versioned` so the envelope and the spec agree
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct Unwrap {
+    // Sole outer key such as "symbols"
+    pub key: Option<String>,
+    // Inner object only holds a "response" list
+    pub response: bool,
+}
+
+impl Unwrap {
+    pub fn apply(&self, mut body: Value) -> Value {
+        if let Some(key) = &self.key {
+            body = body.get_mut(key).map(Value::take).unwrap_or_default();
+        }
+        if self.response {
+            body = body.get_mut("response").map(Value::take).unwrap_or_default();
+        }
+        body
+    }
+}
+
+/// Unwrap rules keyed by "METHOD /route" with the route as registered, without the prefix
+pub(crate) type UnwrapRules = HashMap<String, Unwrap>;
+
+pub(crate) fn rule_key(method: &str, route: &str) -> String {
+    format!("{} {}", method.to_uppercase(), route)
+}
+
+fn is_json(response: &Response, content_type: &str) -> bool {
+    response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with(content_type))
+}
+
+/// Rewrites JSON and problem responses under /api/v1 into
+/// `{"data", "meta", "errors"}`, streams and files pass through untouched
+pub(crate) async fn envelope(State(rules): State<Arc<UnwrapRules>>, request: Request, next: Next) -> Response {
+    let started = Instant::now();
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|m| m.as_str().trim_start_matches(V1_PREFIX).to_string());
+    let unwrap = route
+        .and_then(|route| rules.get(&rule_key(request.method().as_str(), &route)).cloned())
+        .unwrap_or_default();
+
+    let response = next.run(request).await;
+    let is_problem = is_json(&response, PROBLEM_CONTENT_TYPE);
+    if !is_problem && !is_json(&response, JSON_CONTENT_TYPE) {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let body: Value = match axum::body::to_bytes(body, usize::MAX).await {
+        Ok(bytes) => serde_json::from_slice(&bytes).unwrap_or_default(),
+        Err(e) => return AppError::Internal(format!("Failed to read response body: {e}")).into_response(),
+    };
+
+    let (data, errors) = if is_problem { (Value::Null, vec![body]) } else { (unwrap.apply(body), vec![]) };
+    let meta = Meta {
+        count: data.as_array().map(Vec::len),
+        pagination: parts.extensions.get::<Pagination>().cloned(),
+        timings: Timings { total_ms: started.elapsed().as_secs_f64() * 1000.0 },
+    };
+
+    let bytes = serde_json::to_vec(&json!({ "data": data, "meta": meta, "errors": errors })).unwrap_or_default();
+    parts.headers.insert(header::CONTENT_TYPE, HeaderValue::from_static(JSON_CONTENT_TYPE));
+    parts.headers.remove(header::CONTENT_LENGTH);
+    Response::from_parts(parts, Body::from(bytes))
+}
+
+/// Marks the unversioned routes as deprecated and points at their /api/v1 successor
+pub(crate) async fn deprecated(request: Request, next: Next) -> Response {
+    let successor = format!("<{V1_PREFIX}{}>; rel=\"successor-version\"", request.uri().path());
+    let mut response = next.run(request).await;
+
+    let headers = response.headers_mut();
+    headers.insert("deprecation", HeaderValue::from_static(LEGACY_DEPRECATED_AT));
+    if let Ok(link) = HeaderValue::from_str(&successor) {
+        headers.append(header::LINK, link);
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unwrap_strips_outer_key_and_response_list() {
+        let body = json!({ "symbols": { "response": [{ "name": "/ES" }] } });
+
+        let both = Unwrap { key: Some("symbols".to_string()), response: true };
+        assert_eq!(both.apply(body.clone()), json!([{ "name": "/ES" }]));
+
+        let key_only = Unwrap { key: Some("symbols".to_string()), response: false };
+        assert_eq!(key_only.apply(body.clone()), json!({ "response": [{ "name": "/ES" }] }));
+
+        assert_eq!(Unwrap::default().apply(body.clone()), body);
+    }
+}
@@ -0,0 +1,206 @@
+use axum::{
+    extract::{
+        State,
+        ws::{Message, WebSocket, WebSocketUpgrade},
+    },
+    response::{
+        IntoResponse,
+        sse::{Event, KeepAlive, Sse},
+    },
+};
+use futures::{Stream, StreamExt};
+use std::{convert::Infallible, sync::Arc, time::Duration};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info, warn};
+
+use crate::{
+    AppState,
+    models::{
+        event::{StrategyEvent, StrategyNotification},
+        strategy::Strategy,
+    },
+};
+use crate::telemetry::ObserveQuery;
+
+pub(crate) const STRATEGY_CHANNEL: &str = "strategy_events";
+pub(crate) const EVENT_BUFFER: usize = 256;
+
+async fn next_event(receiver: &mut broadcast::Receiver<StrategyEvent>) -> Option<StrategyEvent> {
+    loop {
+        match receiver.recv().await {
+            Ok(event) => return Some(event),
+            Err(RecvError::Lagged(skipped)) => {
+                warn!("Event subscriber lagged, skipped {skipped} strategy events");
+            }
+            Err(RecvError::Closed) => return None,
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/events",
+    tag = "strategies",
+    responses((status = 200, description = "Server-sent strategy events, named by kind", body = StrategyEvent, content_type = "text/event-stream")),
+)]
+pub(crate) async fn events_sse(
+    State(state): State<Arc<AppState>>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let stream = futures::stream::unfold(state.events.subscribe(), |mut receiver| async move {
+        let event = next_event(&mut receiver).await?;
+        let sse = Event::default()
+            .event(event.kind.to_string())
+            .json_data(&event)
+            .unwrap_or_else(|e| Event::default().event("error").data(e.to_string()));
+        Some((Ok(sse), receiver))
+    });
+
+    // End the stream on shutdown so it does not hold the drain open until the timeout
+    Sse::new(stream.take_until(state.shutdown.clone().cancelled_owned())).keep_alive(KeepAlive::default())
+}
+
+#[utoipa::path(
+    get,
+    path = "/events/ws",
+    tag = "strategies",
+    responses((status = 101, description = "WebSocket carrying each strategy event as a JSON text message")),
+)]
+pub(crate) async fn events_ws(
+    ws: WebSocketUpgrade,
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    let receiver = state.events.subscribe();
+    let shutdown = state.shutdown.clone();
+    ws.on_upgrade(move |socket| forward_events(socket, receiver, shutdown))
+}
+
+async fn forward_events(
+    mut socket: WebSocket,
+    mut receiver: broadcast::Receiver<StrategyEvent>,
+    shutdown: CancellationToken,
+) {
+    loop {
+        tokio::select! {
+            _ = shutdown.cancelled() => {
+                let _ = socket.send(Message::Close(None)).await;
+                break;
+            }
+            event = next_event(&mut receiver) => {
+                let Some(event) = event else { break };
+                let payload = match serde_json::to_string(&event) {
+                    Ok(payload) => payload,
+                    Err(e) => {
+                        error!("Failed to serialize strategy event: {e}");
+                        continue;
+                    }
+                };
+                if socket.send(Message::Text(payload.into())).await.is_err() {
+                    break;
+                }
+            }
+            incoming = socket.recv() => {
+                match incoming {
+                    Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                    Some(Ok(_)) => {}
+                }
+            }
+        }
+    }
+}
+
+async fn fetch_strategy(state: &AppState, notification: &StrategyNotification) -> Result<Option<Strategy>, sqlx::Error> {
+    let query = r#"
+    SELECT
+        *
+    FROM
+        strategy
+    WHERE
+        local_id = $1
+    "#;
+
+    sqlx::query_as::<_, Strategy>(query)
+        .bind(notification.local_id)
+        .fetch_optional(&state.db.pool)
+        .observe("event_strategy")
+        .await
+}
+
+pub(crate) async fn listen_strategy_events(state: Arc<AppState>, cancel_token: CancellationToken) {
+    let mut listener = match state.db.listener(&[STRATEGY_CHANNEL]).await {
+        Ok(listener) => listener,
+        Err(e) => {
+            error!("Failed to LISTEN on {STRATEGY_CHANNEL}, live updates disabled: {e}");
+            return;
+        }
+    };
+
+    info!("Listening for strategy events on channel {STRATEGY_CHANNEL}");
+    loop {
+        let notification = tokio::select! {
+            _ = cancel_token.cancelled() => break,
+            notification = listener.recv() => notification,
+        };
+
+        let notification = match notification {
+            Ok(notification) => notification,
+            Err(e) => {
+                // PgListener reconnects on the next recv, back off so we don't spin
+                warn!("Strategy listener connection error: {e}");
+                tokio::time::sleep(Duration::from_secs(1)).await;
+                continue;
+            }
+        };
+
+        let payload = match serde_json::from_str::<StrategyNotification>(notification.payload()) {
+            Ok(payload) => payload,
+            Err(e) => {
+                warn!("Ignoring malformed strategy notification '{}': {e}", notification.payload());
+                continue;
+            }
+        };
+
+        match fetch_strategy(&state, &payload).await {
+            Ok(Some(strategy)) => {
+                // No subscribers is not an error, the event is simply dropped
+                let _ = state.events.send(StrategyEvent::new(&payload.op, strategy));
+            }
+            Ok(None) => warn!("Strategy {} from notification no longer exists", payload.local_id),
+            Err(e) => error!("Failed to load strategy {}: {e}", payload.local_id),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{Router, routing::get};
+    use tokio_tungstenite::tungstenite;
+
+    const WAIT: Duration = Duration::from_secs(5);
+
+    #[tokio::test]
+    async fn test_sse_stream_ends_on_shutdown() {
+        let (state, _) = AppState::for_tests(serde_json::json!({}));
+        let body = events_sse(State(state.clone())).await.into_response().into_body();
+
+        state.shutdown.cancel();
+        let drained = tokio::time::timeout(WAIT, axum::body::to_bytes(body, usize::MAX)).await;
+        assert!(drained.is_ok_and(|bytes| bytes.is_ok()), "stream stayed open after shutdown");
+    }
+
+    #[tokio::test]
+    async fn test_websocket_closes_on_shutdown() {
+        let (state, _) = AppState::for_tests(serde_json::json!({}));
+        let app = Router::new().route("/events/ws", get(events_ws)).with_state(state.clone());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let (mut socket, _) = tokio_tungstenite::connect_async(format!("ws://{addr}/events/ws")).await.unwrap();
+        state.shutdown.cancel();
+
+        let frame = tokio::time::timeout(WAIT, socket.next()).await.unwrap();
+        assert!(matches!(frame, Some(Ok(tungstenite::Message::Close(_)))), "{frame:?}");
+    }
+}
@@ -0,0 +1,176 @@
+use axum::{
+    body::Bytes,
+    extract::{
+        FromRequest, FromRequestParts, Path, Request, State,
+        rejection::{BytesRejection, JsonRejection},
+    },
+    http::request::Parts,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use chrono::NaiveDate;
+use serde::de::DeserializeOwned;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::{AppState, models::settings::ApiConfig};
+
+use super::common::AppError;
+
+/// Query string extractor that rejects with problem details and checks any
+/// from/to pair against the configured maximum range
+pub(crate) struct ApiQuery<T>(pub T);
+
+impl<T> FromRequestParts<Arc<AppState>> for ApiQuery<T>
+where
+    T: DeserializeOwned,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &Arc<AppState>) -> Result<Self, Self::Rejection> {
+        deserialize_query(parts.uri.query().unwrap_or_default(), &state.settings.api).map(ApiQuery)
+    }
+}
+
+/// JSON body extractor that rejects with problem details
+pub(crate) struct ApiJson<T>(pub T);
+
+impl<T, S> FromRequest<S> for ApiJson<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        match axum::Json::<T>::from_request(req, state).await {
+            Ok(axum::Json(value)) => Ok(ApiJson(value)),
+            Err(JsonRejection::JsonDataError(e)) => Err(serde_error(e.body_text(), AppError::InvalidBody)),
+            Err(e) => Err(AppError::InvalidBody(e.body_text())),
+        }
+    }
+}
+
+/// Raw body extractor that rejects with problem details, including a body over
+/// the route's `DefaultBodyLimit`
+pub(crate) struct ApiBytes(pub Bytes);
+
+impl<S> FromRequest<S> for ApiBytes
+where
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        match Bytes::from_request(req, state).await {
+            Ok(bytes) => Ok(ApiBytes(bytes)),
+            Err(BytesRejection::FailedToBufferBody(e)) if e.status() == axum::http::StatusCode::PAYLOAD_TOO_LARGE => {
+                Err(AppError::PayloadTooLarge(e.body_text()))
+            }
+            Err(e) => Err(AppError::InvalidBody(e.body_text())),
+        }
+    }
+}
+
+/// Path extractor that rejects with problem details
+pub(crate) struct ApiPath<T>(pub T);
+
+impl<T, S> FromRequestParts<S> for ApiPath<T>
+where
+    T: DeserializeOwned + Send,
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        Path::<T>::from_request_parts(parts, state)
+            .await
+            .map(|Path(value)| ApiPath(value))
+            .map_err(|e| AppError::InvalidPath(e.body_text()))
+    }
+}
+
+// Only the range fields, everything else in the query string is ignored
+#[derive(serde::Deserialize)]
+struct RangeProbe {
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+    compare_from: Option<NaiveDate>,
+    compare_to: Option<NaiveDate>,
+}
+
+fn serde_error(message: String, otherwise: fn(String) -> AppError) -> AppError {
+    if message.contains("unknown variant") {
+        AppError::UnknownValue(message)
+    } else {
+        otherwise(message)
+    }
+}
+
+pub(crate) fn validate_range(from: NaiveDate, to: NaiveDate, config: &ApiConfig) -> Result<(), AppError> {
+    if from > to {
+        return Err(AppError::InvalidDateRange { from, to });
+    }
+    let days = (to - from).num_days();
+    if days > config.max_range_days {
+        return Err(AppError::RangeTooLarge { days, max_days: config.max_range_days });
+    }
+    Ok(())
+}
+
+pub(crate) fn deserialize_query<T: DeserializeOwned>(raw: &str, config: &ApiConfig) -> Result<T, AppError> {
+    let value = serde_urlencoded::from_str::<T>(raw).map_err(|e| serde_error(e.to_string(), AppError::InvalidQuery))?;
+
+    if let Ok(probe) = serde_urlencoded::from_str::<RangeProbe>(raw)
+        && let (Some(from), Some(to)) = (probe.from, probe.to)
+    {
+        validate_range(from, to, config)?;
+        if probe.compare_from.is_some() || probe.compare_to.is_some() {
+            validate_range(probe.compare_from.unwrap_or(from), probe.compare_to.unwrap_or(to), config)?;
+        }
+    }
+    Ok(value)
+}
+
+// Bounds the time to produce a response, streams already returned keep running
+pub(crate) async fn request_timeout(State(state): State<Arc<AppState>>, request: Request, next: Next) -> Response {
+    let limit = Duration::from_secs(state.settings.api.request_timeout_secs);
+    match tokio::time::timeout(limit, next.run(request)).await {
+        Ok(response) => response,
+        Err(_) => AppError::Timeout(format!("Request did not complete within {}s", limit.as_secs())).into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::service::common::SimpleRequest;
+
+    #[test]
+    fn test_query_errors_map_to_stable_codes() {
+        let config = ApiConfig { max_range_days: 31, ..Default::default() };
+        let code = |raw: &str| deserialize_query::<SimpleRequest>(raw, &config).err().map(|e| e.code());
+
+        assert_eq!(code("from=2025-01-01&to=2025-01-31"), None);
+        assert_eq!(code("from=2025-01-01"), Some("invalid_query"));
+        assert_eq!(code("from=2025-02-01&to=2025-01-01"), Some("invalid_date_range"));
+        assert_eq!(code("from=2025-01-01&to=2025-06-01"), Some("range_too_large"));
+
+        let sort = deserialize_query::<crate::models::symbol::SymbolRequest>("sort=biggest", &config);
+        assert_eq!(sort.err().map(|e| e.code()), Some("unknown_value"));
+    }
+
+    #[tokio::test]
+    async fn test_oversized_body_is_a_problem_response() {
+        // Over axum's 2MB default, the upload route lowers it with DefaultBodyLimit
+        let request = Request::new(axum::body::Body::from(vec![0u8; 3 * 1024 * 1024]));
+        let Err(err) = ApiBytes::from_request(request, &()).await else {
+            panic!("an oversized body was accepted");
+        };
+        assert_eq!(err.code(), "payload_too_large");
+
+        let response = err.into_response();
+        assert_eq!(response.status(), axum::http::StatusCode::PAYLOAD_TOO_LARGE);
+        assert_eq!(response.headers()[axum::http::header::CONTENT_TYPE], "application/problem+json");
+    }
+}
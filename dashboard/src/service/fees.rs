@@ -0,0 +1,183 @@
+use axum::{extract::State, response::IntoResponse};
+use rust_decimal::Decimal;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use crate::{
+    AppState,
+    calendar::TradingCalendar,
+    models::{
+        account::AccountDailySnapshot,
+        fees::{FeeBucket, FeeReconciliation, FeeReport, FeeRequest, FlippedTrade},
+        metrics::MetricsFilter,
+        strategy::Strategy,
+    },
+};
+
+use crate::openapi::FeesBody;
+use super::account::fetch_snapshots;
+use super::common::AppError;
+use super::metrics::fetch_closed;
+use super::extract::ApiQuery;
+
+fn group_fees(rows: &[Strategy], key: impl Fn(&Strategy) -> String) -> Vec<FeeBucket> {
+    let mut buckets: BTreeMap<String, FeeBucket> = BTreeMap::new();
+    for s in rows {
+        let key = key(s);
+        buckets.entry(key.clone()).or_insert_with(|| FeeBucket::new(key)).accumulate(s);
+    }
+    buckets.into_values().map(FeeBucket::finish).collect()
+}
+
+fn by_fees_desc(mut buckets: Vec<FeeBucket>) -> Vec<FeeBucket> {
+    buckets.sort_by(|a, b| b.fees.cmp(&a.fees).then(a.key.cmp(&b.key)));
+    buckets
+}
+
+fn flipped_trades(rows: &[Strategy]) -> Vec<FlippedTrade> {
+    let mut flipped: Vec<FlippedTrade> = rows
+        .iter()
+        .filter_map(|s| {
+            let stats = &s.risk.stats;
+            let net = stats.pnl - stats.fee;
+            (stats.pnl > Decimal::ZERO && net < Decimal::ZERO).then(|| FlippedTrade {
+                local_id: s.local_id,
+                symbol: s.symbol.clone(),
+                contract: s.contract.clone(),
+                exit_time: s.exit_time,
+                pnl: stats.pnl,
+                fee: stats.fee,
+                net,
+            })
+        })
+        .collect();
+    flipped.sort_by_key(|t| t.exit_time);
+    flipped
+}
+
+fn build_fee_report(
+    request: &FeeRequest,
+    currency: &str,
+    calendar: &TradingCalendar,
+    rows: &[Strategy],
+    reconciliation: Vec<FeeReconciliation>,
+) -> FeeReport {
+    let mut totals = FeeBucket::new("total".to_string());
+    for s in rows {
+        totals.accumulate(s);
+    }
+
+    FeeReport {
+        from: request.from,
+        to: request.to,
+        currency: currency.to_string(),
+        period: request.period,
+        totals: totals.finish(),
+        // Keys are ISO dates so the BTreeMap order is chronological
+        by_period: group_fees(rows, |s| request.period.start_of(calendar.booking_date(s.exit_time)).to_string()),
+        by_symbol: by_fees_desc(group_fees(rows, |s| s.symbol.clone())),
+        by_strategy_type: by_fees_desc(group_fees(rows, |s| s.meta.r#type.to_string())),
+        by_asset_type: by_fees_desc(group_fees(rows, |s| s.meta.asset_type.to_string())),
+        flipped_trades: flipped_trades(rows),
+        reconciliation,
+    }
+}
+
+fn reconcile(
+    state: &AppState,
+    rows: &[Strategy],
+    snapshots: &BTreeMap<String, Vec<AccountDailySnapshot>>,
+) -> Result<Vec<FeeReconciliation>, AppError> {
+    let mut trade_fees: BTreeMap<&str, Decimal> = BTreeMap::new();
+    for s in rows {
+        *trade_fees.entry(s.account.account_id.as_str()).or_default() += s.risk.stats.fee;
+    }
+
+    let mut accounts: Vec<&str> = trade_fees.keys().copied().chain(snapshots.keys().map(String::as_str)).collect();
+    accounts.sort();
+    accounts.dedup();
+
+    accounts
+        .into_iter()
+        .map(|account_id| {
+            let days = snapshots.get(account_id).map(Vec::as_slice).unwrap_or_default();
+            let account_fees = days
+                .iter()
+                .map(|d| state.fx.convert(d.cash_flows.fees, &d.currency, d.date))
+                .sum::<Result<Decimal, AppError>>()?;
+            let trade_fees = trade_fees.get(account_id).copied().unwrap_or_default();
+            Ok(FeeReconciliation {
+                account_id: account_id.to_string(),
+                trade_fees,
+                account_fees,
+                difference: account_fees - trade_fees,
+                snapshot_days: days.len(),
+            })
+        })
+        .collect()
+}
+
+#[utoipa::path(
+    get,
+    path = "/fees",
+    tag = "analytics",
+    params(FeeRequest),
+    responses((status = 200, description = "Fees by period, symbol, type and asset, reconciled against account snapshots unless tags or strategy_type are set", body = FeesBody)),
+)]
+pub(crate) async fn fees(ApiQuery(request): ApiQuery<FeeRequest>, State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let filter = MetricsFilter {
+        from: request.from,
+        to: request.to,
+        account_id: request.account_id.clone(),
+        tags: request.tags.clone(),
+        strategy_type: request.strategy_type,
+    };
+
+    // Snapshot fees cover the whole account, so they only reconcile against unfiltered trades
+    let reconcilable = request.tags.is_none() && request.strategy_type.is_none();
+
+    let result = async {
+        let rows = fetch_closed(&state, &filter).await?;
+        let reconciliation = if reconcilable {
+            let snapshots = fetch_snapshots(&state, request.from, request.to, request.account_id.as_deref()).await?;
+            reconcile(&state, &rows, &snapshots)?
+        } else {
+            Vec::new()
+        };
+        Ok::<_, AppError>((rows, reconciliation))
+    }
+    .await;
+
+    match result {
+        Ok((rows, reconciliation)) => {
+            build_fee_report(&request, state.fx.reporting_currency(), &state.calendar, &rows, reconciliation)
+                .into_response()
+        }
+        Err(e) => e.into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::fees::FeePeriod;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_week_and_month_periods_start_on_monday_and_first() {
+        let date = chrono::NaiveDate::from_ymd_opt(2025, 3, 13).unwrap();
+        assert_eq!(FeePeriod::Week.start_of(date).to_string(), "2025-03-10");
+        assert_eq!(FeePeriod::Month.start_of(date).to_string(), "2025-03-01");
+    }
+
+    #[test]
+    fn test_fee_share_is_against_pre_fee_winners() {
+        let mut bucket = FeeBucket::new("total".to_string());
+        bucket.fees = dec!(25);
+        bucket.gross_profit = dec!(100);
+        bucket.trade_count = 5;
+        let bucket = bucket.finish();
+        assert_eq!(bucket.fee_share_of_gross_profit, Some(0.25));
+        assert_eq!(bucket.avg_fee, Some(dec!(5)));
+    }
+}
@@ -1,5 +1,335 @@
-use axum::http::StatusCode;
+use axum::{Json, extract::State, http::StatusCode, response::IntoResponse};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::error;
+use utoipa::ToSchema;
 
-pub(crate) async fn health() -> StatusCode {
+use crate::{AppState, auth::AdminOnly};
+
+use super::common::AppError;
+use crate::telemetry::ObserveQuery;
+
+const GIT_SHA: &str = env!("BUILD_GIT_SHA");
+const BUILD_TIMESTAMP: &str = env!("BUILD_TIMESTAMP");
+
+// Readiness probes run often, a slow database counts as unavailable
+const PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+
+// Columns Strategy::from_row reads, the table itself belongs to the trading service
+const STRATEGY_COLUMNS: &[&str] = &[
+    "local_id",
+    "symbol",
+    "entry_time",
+    "exit_time",
+    "status",
+    "metadata",
+    "risk",
+    "account",
+];
+
+#[derive(Serialize, ToSchema, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+#[derive(Serialize, ToSchema)]
+pub(crate) struct BuildInfo {
+    pub version: String,
+    pub git_sha: String,
+    pub built_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub(crate) struct PoolCheck {
+    pub status: CheckStatus,
+    pub size: u32,
+    pub idle: usize,
+    pub max_connections: u32,
+}
+
+#[derive(Serialize, ToSchema)]
+pub(crate) struct RoundTripCheck {
+    pub status: CheckStatus,
+    pub latency_ms: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub(crate) struct SchemaCheck {
+    pub status: CheckStatus,
+    // Required strategy columns that are absent, every column when the table is missing
+    pub missing_columns: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub(crate) struct LogShippingCheck {
+    pub status: CheckStatus,
+    // False when the CloudWatch layer failed to start and logs only go to stdout
+    pub enabled: bool,
+    pub backlog: usize,
+    pub capacity: usize,
+}
+
+#[derive(Serialize, ToSchema)]
+pub(crate) struct HealthChecks {
+    pub database_pool: PoolCheck,
+    pub database_round_trip: RoundTripCheck,
+    pub schema: SchemaCheck,
+    pub log_shipping: LogShippingCheck,
+}
+
+#[derive(Serialize, ToSchema)]
+pub(crate) struct HealthDetails {
+    // Worst status among the checks, fail while shutting down
+    pub status: CheckStatus,
+    pub shutting_down: bool,
+    pub build: BuildInfo,
+    // Settings file path or S3 object the process was configured from
+    pub config_source: String,
+    pub checks: HealthChecks,
+}
+
+fn build_info() -> BuildInfo {
+    BuildInfo {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        git_sha: GIT_SHA.to_string(),
+        built_at: BUILD_TIMESTAMP.parse().ok().and_then(|secs| DateTime::from_timestamp(secs, 0)),
+    }
+}
+
+fn check_pool(state: &AppState) -> PoolCheck {
+    let pool = &state.db.pool;
+    let size = pool.size();
+    let idle = pool.num_idle();
+    let max_connections = pool.options().get_max_connections();
+    // Every connection checked out means the next query queues
+    let status = if size >= max_connections && idle == 0 { CheckStatus::Warn } else { CheckStatus::Pass };
+    PoolCheck { status, size, idle, max_connections }
+}
+
+async fn check_round_trip(state: &AppState) -> RoundTripCheck {
+    let started = Instant::now();
+    let query = sqlx::query_scalar::<_, i32>("SELECT 1").fetch_one(&state.db.pool).observe_one("health_ping");
+    let result = tokio::time::timeout(PROBE_TIMEOUT, query).await;
+    let latency_ms = started.elapsed().as_secs_f64() * 1000.0;
+
+    let error = match result {
+        Ok(Ok(_)) => None,
+        Ok(Err(e)) => {
+            error!("Health check query failed: {e}");
+            Some(AppError::DatabaseError(e).detail())
+        }
+        Err(_) => Some(format!("No response within {}s", PROBE_TIMEOUT.as_secs())),
+    };
+    let status = if error.is_some() { CheckStatus::Fail } else { CheckStatus::Pass };
+    RoundTripCheck { status, latency_ms, error }
+}
+
+async fn check_schema(state: &AppState) -> SchemaCheck {
+    let query = r#"
+    SELECT
+        column_name::text
+    FROM
+        information_schema.columns
+    WHERE
+        table_schema = current_schema()
+    AND table_name = 'strategy'
+    "#;
+
+    let columns = sqlx::query_scalar::<_, String>(query).fetch_all(&state.db.pool).observe("health_schema");
+    let result = tokio::time::timeout(PROBE_TIMEOUT, columns).await;
+    match result {
+        Ok(Ok(columns)) => {
+            let missing_columns: Vec<String> = STRATEGY_COLUMNS
+                .iter()
+                .filter(|required| !columns.iter().any(|c| c == *required))
+                .map(|c| c.to_string())
+                .collect();
+            let status = if missing_columns.is_empty() { CheckStatus::Pass } else { CheckStatus::Fail };
+            SchemaCheck { status, missing_columns, error: None }
+        }
+        Ok(Err(e)) => {
+            error!("Schema check failed: {e}");
+            SchemaCheck {
+                status: CheckStatus::Fail,
+                missing_columns: vec![],
+                error: Some(AppError::DatabaseError(e).detail()),
+            }
+        }
+        Err(_) => SchemaCheck {
+            status: CheckStatus::Fail,
+            missing_columns: vec![],
+            error: Some(format!("No response within {}s", PROBE_TIMEOUT.as_secs())),
+        },
+    }
+}
+
+fn check_log_shipping(state: &AppState) -> LogShippingCheck {
+    log_shipping_check(state.logs.as_ref().map(|logs| (logs.backlog(), logs.queue_capacity())))
+}
+
+// Backlog and capacity of the CloudWatch queue, None when logs only go to stdout
+fn log_shipping_check(queue: Option<(usize, usize)>) -> LogShippingCheck {
+    match queue {
+        Some((backlog, capacity)) => {
+            // A full queue drops new entries
+            let status = if backlog >= capacity {
+                CheckStatus::Fail
+            } else if backlog * 2 >= capacity {
+                CheckStatus::Warn
+            } else {
+                CheckStatus::Pass
+            };
+            LogShippingCheck { status, enabled: true, backlog, capacity }
+        }
+        None => LogShippingCheck { status: CheckStatus::Warn, enabled: false, backlog: 0, capacity: 0 },
+    }
+}
+
+fn overall_status(checks: &HealthChecks, shutting_down: bool) -> CheckStatus {
+    if shutting_down {
+        return CheckStatus::Fail;
+    }
+    [
+        checks.database_pool.status,
+        checks.database_round_trip.status,
+        checks.schema.status,
+        checks.log_shipping.status,
+    ]
+    .into_iter()
+    .max()
+    .unwrap_or(CheckStatus::Pass)
+}
+
+async fn is_ready(state: &AppState) -> bool {
+    !state.shutdown.is_cancelled() && check_round_trip(state).await.status == CheckStatus::Pass
+}
+
+#[utoipa::path(
+    get,
+    path = "/livez",
+    tag = "health",
+    security(()),
+    responses((status = 200, description = "Process is up, never checks dependencies")),
+)]
+pub(crate) async fn livez() -> StatusCode {
     StatusCode::OK
 }
+
+#[utoipa::path(
+    get,
+    path = "/readyz",
+    tag = "health",
+    security(()),
+    responses(
+        (status = 200, description = "Accepting traffic, the database answers"),
+        (status = 503, description = "Shutting down or the database is unreachable"),
+    ),
+)]
+pub(crate) async fn readyz(State(state): State<Arc<AppState>>) -> StatusCode {
+    // Fails first on shutdown so the load balancer stops routing before the listener closes
+    if is_ready(&state).await { StatusCode::OK } else { StatusCode::SERVICE_UNAVAILABLE }
+}
+
+#[utoipa::path(
+    get,
+    path = "/health",
+    tag = "health",
+    security(()),
+    responses(
+        (status = 200, description = "Same as /readyz, kept for existing health check configuration"),
+        (status = 503, description = "Shutting down or the database is unreachable"),
+    ),
+)]
+pub(crate) async fn health(state: State<Arc<AppState>>) -> StatusCode {
+    readyz(state).await
+}
+
+#[utoipa::path(
+    get,
+    path = "/health/details",
+    tag = "health",
+    responses(
+        (status = 200, description = "Every check passed or only warned, admin only", body = HealthDetails),
+        (status = 503, description = "At least one check failed or the process is shutting down", body = HealthDetails),
+    ),
+)]
+pub(crate) async fn details(_admin: AdminOnly, State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let (database_round_trip, schema) = tokio::join!(check_round_trip(&state), check_schema(&state));
+    let checks = HealthChecks {
+        database_pool: check_pool(&state),
+        database_round_trip,
+        schema,
+        log_shipping: check_log_shipping(&state),
+    };
+
+    let shutting_down = state.shutdown.is_cancelled();
+    let status = overall_status(&checks, shutting_down);
+
+    let code = if status == CheckStatus::Fail { StatusCode::SERVICE_UNAVAILABLE } else { StatusCode::OK };
+    let body = HealthDetails {
+        status,
+        shutting_down,
+        build: build_info(),
+        config_source: state.config_source.clone(),
+        checks,
+    };
+    (code, Json(body))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_readiness_fails_once_shutdown_starts() {
+        let (state, _) = AppState::for_tests(serde_json::json!({}));
+        state.shutdown.cancel();
+        assert_eq!(readyz(State(state.clone())).await, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(health(State(state)).await, StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    fn passing_checks() -> HealthChecks {
+        HealthChecks {
+            database_pool: PoolCheck { status: CheckStatus::Pass, size: 2, idle: 1, max_connections: 10 },
+            database_round_trip: RoundTripCheck { status: CheckStatus::Pass, latency_ms: 1.0, error: None },
+            schema: SchemaCheck { status: CheckStatus::Pass, missing_columns: vec![], error: None },
+            log_shipping: log_shipping_check(Some((0, 100))),
+        }
+    }
+
+    #[test]
+    fn test_log_shipping_warns_at_half_and_fails_when_full() {
+        let status = |queue| log_shipping_check(queue).status;
+        assert_eq!(status(Some((49, 100))), CheckStatus::Pass);
+        assert_eq!(status(Some((50, 100))), CheckStatus::Warn);
+        assert_eq!(status(Some((99, 100))), CheckStatus::Warn);
+        assert_eq!(status(Some((100, 100))), CheckStatus::Fail);
+
+        let stdout_only = log_shipping_check(None);
+        assert_eq!((stdout_only.status, stdout_only.enabled), (CheckStatus::Warn, false));
+    }
+
+    #[test]
+    fn test_overall_status_is_the_worst_check() {
+        assert_eq!(overall_status(&passing_checks(), false), CheckStatus::Pass);
+
+        let mut checks = passing_checks();
+        checks.database_pool.status = CheckStatus::Warn;
+        assert_eq!(overall_status(&checks, false), CheckStatus::Warn);
+
+        checks.schema.status = CheckStatus::Fail;
+        assert_eq!(overall_status(&checks, false), CheckStatus::Fail);
+
+        // Shutting down fails even when every check passes
+        assert_eq!(overall_status(&passing_checks(), true), CheckStatus::Fail);
+    }
+}
@@ -0,0 +1,343 @@
+use axum::{
+    extract::{Request, State},
+    http::{HeaderMap, HeaderValue, StatusCode, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use chrono::Utc;
+use sqlx::types::Uuid;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tracing::warn;
+
+use crate::{
+    AppState,
+    auth::AdminOnly,
+    models::journal::{Attachment, AttachmentRequest, JournalEntry, JournalListRequest, JournalResponse, JournalUpdate},
+};
+
+use crate::openapi::JournalBody;
+use super::common::{AppError, parse_tags};
+use super::extract::{ApiBytes, ApiJson, ApiPath, ApiQuery};
+use crate::telemetry::ObserveQuery;
+
+async fn strategy_exists(state: &AppState, local_id: Uuid) -> Result<bool, AppError> {
+    sqlx::query_scalar::<_, bool>("SELECT EXISTS (SELECT 1 FROM strategy WHERE local_id = $1)")
+        .bind(local_id)
+        .fetch_one(&state.db.pool)
+        .observe_one("strategy_exists")
+        .await
+        .map_err(AppError::DatabaseError)
+}
+
+fn journal_not_found(local_id: Uuid) -> AppError {
+    AppError::NotFound(format!("No journal entry for strategy {local_id}"))
+}
+
+// Keep uploads inside the strategy's directory whatever the client sends as a name
+fn sanitize_file_name(name: &str) -> String {
+    let cleaned: String = name
+        .rsplit(['/', '\\'])
+        .next()
+        .unwrap_or_default()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_') { c } else { '_' })
+        .collect();
+    let cleaned = cleaned.trim_start_matches('.');
+    if cleaned.is_empty() { "attachment".to_string() } else { cleaned.to_string() }
+}
+
+/// Uploads are served from the API origin, so whatever the stored type or extension
+/// the browser must download them rather than render them, e.g. an uploaded .html
+pub(crate) async fn attachment_headers(request: Request, next: Next) -> Response {
+    let mut response = next.run(request).await;
+    let headers = response.headers_mut();
+    headers.insert(header::X_CONTENT_TYPE_OPTIONS, HeaderValue::from_static("nosniff"));
+    if response.status().is_success() {
+        let headers = response.headers_mut();
+        headers.insert(header::CONTENT_TYPE, HeaderValue::from_static("application/octet-stream"));
+        headers.insert(header::CONTENT_DISPOSITION, HeaderValue::from_static("attachment"));
+    }
+    response
+}
+
+// Entries tagged with any of the comma separated tags, newest first
+pub(crate) async fn fetch_journal(state: &AppState, tags: &Option<String>) -> Result<Vec<JournalEntry>, AppError> {
+    let query = r#"
+    SELECT
+        *
+    FROM
+        journal
+    WHERE
+        ($1::text[] IS NULL OR tags && $1)
+    ORDER BY
+        updated_at DESC
+    "#;
+
+    sqlx::query_as::<_, JournalEntry>(query)
+        .bind(parse_tags(tags))
+        .fetch_all(&state.db.pool)
+        .observe("journal_list")
+        .await
+        .map_err(AppError::DatabaseError)
+}
+
+#[utoipa::path(
+    get,
+    path = "/journal",
+    operation_id = "list_journal",
+    tag = "journal",
+    params(JournalListRequest),
+    responses((status = 200, description = "Journal entries", body = JournalBody)),
+)]
+pub(crate) async fn list(
+    ApiQuery(request): ApiQuery<JournalListRequest>,
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    match fetch_journal(&state, &request.tags).await {
+        Ok(rows) => JournalResponse { response: rows }.into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/journal/{local_id}",
+    operation_id = "get_journal",
+    tag = "journal",
+    params(("local_id" = Uuid, Path, description = "Strategy the entry belongs to")),
+    responses((status = 200, description = "The strategy's journal entry", body = JournalBody)),
+)]
+pub(crate) async fn get(ApiPath(local_id): ApiPath<Uuid>, State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let result = sqlx::query_as::<_, JournalEntry>("SELECT * FROM journal WHERE local_id = $1")
+        .bind(local_id)
+        .fetch_optional(&state.db.pool)
+        .observe("journal_get")
+        .await
+        .map_err(AppError::DatabaseError);
+
+    match result {
+        Ok(Some(entry)) => JournalResponse { response: vec![entry] }.into_response(),
+        Ok(None) => journal_not_found(local_id).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/journal/{local_id}",
+    operation_id = "create_journal",
+    tag = "journal",
+    params(("local_id" = Uuid, Path, description = "Strategy the entry belongs to")),
+    request_body = JournalUpdate,
+    responses((status = 201, description = "Entry created, admin only", body = JournalBody)),
+)]
+pub(crate) async fn create(
+    _admin: AdminOnly,
+    ApiPath(local_id): ApiPath<Uuid>,
+    State(state): State<Arc<AppState>>,
+    ApiJson(request): ApiJson<JournalUpdate>,
+) -> impl IntoResponse {
+    match strategy_exists(&state, local_id).await {
+        Ok(true) => {}
+        Ok(false) => return AppError::NotFound(format!("Unknown strategy {local_id}")).into_response(),
+        Err(e) => return e.into_response(),
+    }
+
+    let query = r#"
+    INSERT INTO journal (local_id, notes, tags)
+    VALUES ($1, $2, $3)
+    RETURNING *
+    "#;
+
+    let result = sqlx::query_as::<_, JournalEntry>(query)
+        .bind(local_id)
+        .bind(request.notes.clone().unwrap_or_default())
+        .bind(request.normalized_tags().unwrap_or_default())
+        .fetch_one(&state.db.pool)
+        .observe_one("journal_create")
+        .await;
+
+    match result {
+        Ok(entry) => (StatusCode::CREATED, JournalResponse { response: vec![entry] }).into_response(),
+        Err(sqlx::Error::Database(e)) if e.is_unique_violation() => {
+            AppError::Conflict(format!("Journal entry for strategy {local_id} already exists")).into_response()
+        }
+        Err(e) => AppError::DatabaseError(e).into_response(),
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/journal/{local_id}",
+    operation_id = "update_journal",
+    tag = "journal",
+    params(("local_id" = Uuid, Path, description = "Strategy the entry belongs to")),
+    request_body = JournalUpdate,
+    responses((status = 200, description = "Entry updated, admin only", body = JournalBody)),
+)]
+pub(crate) async fn update(
+    _admin: AdminOnly,
+    ApiPath(local_id): ApiPath<Uuid>,
+    State(state): State<Arc<AppState>>,
+    ApiJson(request): ApiJson<JournalUpdate>,
+) -> impl IntoResponse {
+    let query = r#"
+    UPDATE journal
+    SET
+        notes = COALESCE($2, notes),
+        tags = COALESCE($3, tags),
+        updated_at = now()
+    WHERE
+        local_id = $1
+    RETURNING *
+    "#;
+
+    let result = sqlx::query_as::<_, JournalEntry>(query)
+        .bind(local_id)
+        .bind(request.notes.clone())
+        .bind(request.normalized_tags())
+        .fetch_optional(&state.db.pool)
+        .observe("journal_update")
+        .await
+        .map_err(AppError::DatabaseError);
+
+    match result {
+        Ok(Some(entry)) => JournalResponse { response: vec![entry] }.into_response(),
+        Ok(None) => journal_not_found(local_id).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/journal/{local_id}",
+    operation_id = "delete_journal",
+    tag = "journal",
+    params(("local_id" = Uuid, Path, description = "Strategy the entry belongs to")),
+    responses((status = 204, description = "Entry and its attachments removed, admin only")),
+)]
+pub(crate) async fn delete(
+    _admin: AdminOnly,
+    ApiPath(local_id): ApiPath<Uuid>,
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    let result = sqlx::query("DELETE FROM journal WHERE local_id = $1")
+        .bind(local_id)
+        .execute(&state.db.pool)
+        .observe("journal_delete")
+        .await
+        .map_err(AppError::DatabaseError);
+
+    match result {
+        Ok(done) if done.rows_affected() == 0 => journal_not_found(local_id).into_response(),
+        Ok(_) => {
+            let dir = PathBuf::from(&state.settings.journal.attachments_dir).join(local_id.to_string());
+            if let Err(e) = tokio::fs::remove_dir_all(&dir).await
+                && e.kind() != std::io::ErrorKind::NotFound
+            {
+                warn!("Failed to remove attachments in {}: {e}", dir.display());
+            }
+            StatusCode::NO_CONTENT.into_response()
+        }
+        Err(e) => e.into_response(),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/journal/{local_id}/attachments",
+    tag = "journal",
+    params(("local_id" = Uuid, Path, description = "Strategy the entry belongs to"), AttachmentRequest),
+    request_body(content = Vec<u8>, description = "Raw file, Content-Type is stored with it", content_type = "application/octet-stream"),
+    responses((status = 201, description = "Attachment stored, admin only", body = JournalBody)),
+)]
+pub(crate) async fn upload_attachment(
+    _admin: AdminOnly,
+    ApiPath(local_id): ApiPath<Uuid>,
+    ApiQuery(request): ApiQuery<AttachmentRequest>,
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    ApiBytes(body): ApiBytes,
+) -> impl IntoResponse {
+    if body.is_empty() {
+        return AppError::BadRequest("Attachment body is empty".to_string()).into_response();
+    }
+    match strategy_exists(&state, local_id).await {
+        Ok(true) => {}
+        Ok(false) => return AppError::NotFound(format!("Unknown strategy {local_id}")).into_response(),
+        Err(e) => return e.into_response(),
+    }
+
+    let name = sanitize_file_name(&request.name);
+    let relative = format!("{local_id}/{}-{name}", Uuid::new_v4());
+    let path = PathBuf::from(&state.settings.journal.attachments_dir).join(&relative);
+
+    let written = async {
+        if let Some(dir) = path.parent() {
+            tokio::fs::create_dir_all(dir).await?;
+        }
+        tokio::fs::write(&path, &body).await
+    }
+    .await;
+    if let Err(e) = written {
+        return AppError::Internal(format!("Failed to store attachment {}: {e}", path.display())).into_response();
+    }
+
+    let attachment = Attachment {
+        name,
+        path: relative,
+        content_type: headers
+            .get(header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string),
+        size: body.len() as u64,
+        uploaded_at: Utc::now(),
+    };
+
+    // Attaching to a strategy without notes yet starts its journal entry
+    let query = r#"
+    INSERT INTO journal (local_id, attachments)
+    VALUES ($1, jsonb_build_array($2::jsonb))
+    ON CONFLICT (local_id) DO UPDATE
+    SET
+        attachments = journal.attachments || EXCLUDED.attachments,
+        updated_at = now()
+    RETURNING *
+    "#;
+
+    let result = sqlx::query_as::<_, JournalEntry>(query)
+        .bind(local_id)
+        .bind(sqlx::types::Json(&attachment))
+        .fetch_one(&state.db.pool)
+        .observe_one("journal_attach")
+        .await
+        .map_err(AppError::DatabaseError);
+
+    match result {
+        Ok(entry) => (StatusCode::CREATED, JournalResponse { response: vec![entry] }).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_names_cannot_leave_the_strategy_directory() {
+        assert_eq!(sanitize_file_name("../../etc/passwd"), "passwd");
+        assert_eq!(sanitize_file_name("..\\..\\boot.ini"), "boot.ini");
+        assert_eq!(sanitize_file_name("/abs/path/chart.png"), "chart.png");
+        assert_eq!(sanitize_file_name("notes/"), "attachment");
+    }
+
+    #[test]
+    fn test_file_names_are_never_empty_or_hidden() {
+        assert_eq!(sanitize_file_name(""), "attachment");
+        assert_eq!(sanitize_file_name("."), "attachment");
+        assert_eq!(sanitize_file_name(".."), "attachment");
+        assert_eq!(sanitize_file_name(".env"), "env");
+        assert_eq!(sanitize_file_name("trade plan (v2).pdf"), "trade_plan__v2_.pdf");
+    }
+}
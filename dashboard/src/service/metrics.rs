@@ -1,5 +1,4 @@
 use axum::{
-    extract::Query,
     http::StatusCode,
     response::IntoResponse,
     Json,
@@ -7,22 +6,29 @@ use axum::{
 use rust_decimal::Decimal;
 use rust_decimal::prelude::{ToPrimitive, FromPrimitive};
 use serde_json::json;
+use statrs::distribution::{ContinuousCDF, StudentsT};
 use tracing::info;
- 
+
+use std::collections::BTreeMap;
 use std::sync::Arc;
 
 use crate::{
     AppState,
+    calendar::{TradingCalendar, TRADING_DAYS_PER_YEAR},
     models::{
         metrics::{
-            DrawdownMetrics, ExpectancyMetrics, MetricsRequest, MetricsResponseBody, ProfitFactorMetrics,
+            BootstrapTest, DrawdownMetrics, ExpectancyMetrics, MetricDelta, MetricsComparison, MetricsFilter,
+            MetricsRequest, MetricsResponseBody, ProfitFactorMetrics, SignificanceMetrics, WelchTest,
             RecoveryFactorMetrics, SharpeMetrics, BASE_CAPITAL,
         },
         strategy::{Status, Strategy},
     },
 };
 
-use super::common::AppError;
+use crate::openapi::MetricsBody;
+use super::common::{AppError, parse_tags};
+use super::views::ViewQuery;
+use crate::telemetry::ObserveQuery;
 // Inline helper functions and types for metric calculations
 struct NetsSummary {
     nets: Vec<Decimal>,
@@ -54,15 +60,13 @@ fn derive_nets(rows: &[Strategy]) -> NetsSummary {
     NetsSummary { nets, wins_sum, losses_sum_abs, wins_count, losses_count }
 }
 
-fn daily_from_rows(from: chrono::NaiveDate, to: chrono::NaiveDate, rows: &[Strategy]) -> std::collections::BTreeMap<chrono::NaiveDate, Decimal> {
-    let mut daily: std::collections::BTreeMap<chrono::NaiveDate, Decimal> = std::collections::BTreeMap::new();
-    let mut d = from;
-    while d <= to {
-        daily.insert(d, Decimal::ZERO);
-        d = d.succ_opt().unwrap();
-    }
+// Exits on a weekend or holiday at the end of the range book on the next session,
+// so the buckets run through the session `to` itself would book on
+pub(crate) fn daily_from_rows(from: chrono::NaiveDate, to: chrono::NaiveDate, calendar: &TradingCalendar, rows: &[Strategy]) -> std::collections::BTreeMap<chrono::NaiveDate, Decimal> {
+    let last = calendar.trading_day_on_or_after(to);
+    let mut daily: std::collections::BTreeMap<chrono::NaiveDate, Decimal> = calendar.daily_buckets(from, last);
     for s in rows {
-        let day = s.exit_time.date_naive();
+        let day = calendar.booking_date(s.exit_time);
         let net = s.risk.stats.pnl - s.risk.stats.fee;
         if let Some(v) = daily.get_mut(&day) {
             *v += net;
@@ -149,7 +153,7 @@ fn compute_sharpe(daily: &std::collections::BTreeMap<chrono::NaiveDate, Decimal>
     }
     let sample_days = daily_returns.len();
     let sharpe_tuple = if sample_days >= 2 {
-        let rf_daily = rf_annual / 252.0_f64;
+        let rf_daily = rf_annual / TRADING_DAYS_PER_YEAR;
         let mut excess: Vec<f64> = Vec::with_capacity(sample_days);
         for r in &daily_returns { excess.push(*r - rf_daily); }
         let mean = excess.iter().sum::<f64>() / sample_days as f64;
@@ -157,7 +161,7 @@ fn compute_sharpe(daily: &std::collections::BTreeMap<chrono::NaiveDate, Decimal>
         for r in &excess { var += (r - mean) * (r - mean); }
         var /= sample_days as f64 - 1.0;
         let std = var.sqrt();
-        if std > 0.0 { Some((mean / std * (252.0_f64).sqrt(), mean, std)) } else { None }
+        if std > 0.0 { Some((mean / std * TRADING_DAYS_PER_YEAR.sqrt(), mean, std)) } else { None }
     } else { None };
 
     let (sharpe_opt, mean_opt, vol_opt) = match sharpe_tuple {
@@ -193,8 +197,6 @@ fn compute_expectancy(nets: &[Decimal], wins_sum: Decimal, wins_count: usize, lo
 fn compute_profit_factor(wins_sum: Decimal, losses_sum_abs: Decimal, wins_count: usize, losses_count: usize, trade_count: usize) -> ProfitFactorMetrics {
     let profit_factor = if losses_sum_abs > Decimal::ZERO {
         Some((wins_sum.to_f64().unwrap_or(0.0)) / (losses_sum_abs.to_f64().unwrap_or(1.0)))
-    } else if wins_sum > Decimal::ZERO && trade_count > 0 {
-        None
     } else { None };
 
     ProfitFactorMetrics { profit_factor, gross_profit: wins_sum, gross_loss: losses_sum_abs, wins: wins_count, losses: losses_count, trade_count }
@@ -205,73 +207,302 @@ fn compute_recovery(net_profit: Decimal, max_dd: Decimal) -> RecoveryFactorMetri
     RecoveryFactorMetrics { recovery_factor, net_profit, reference_max_dd: max_dd }
 }
 
+pub(crate) fn build_metrics(from: chrono::NaiveDate, to: chrono::NaiveDate, currency: &str, calendar: &TradingCalendar, rows: &[Strategy]) -> MetricsResponseBody {
+    // Build inputs
+    let NetsSummary { nets, wins_sum, losses_sum_abs, wins_count, losses_count } = derive_nets(rows);
+    let daily = daily_from_rows(from, to, calendar, rows);
+    let equity = equity_from_daily(&daily);
+
+    // Compute metrics
+    let drawdown_aux = compute_drawdown(&equity);
+    // Pull risk-free rate from the most recent available account snapshot in the set
+    let rf_annual = rows
+        .iter()
+        .max_by_key(|s| s.exit_time)
+        .map(|s| s.account.risk_free_annual)
+        .unwrap_or(0.0_f64);
+    let sharpe = compute_sharpe(&daily, rf_annual);
+    let expectancy = compute_expectancy(&nets, wins_sum, wins_count, losses_sum_abs, losses_count);
+    let pf = compute_profit_factor(wins_sum, losses_sum_abs, wins_count, losses_count, nets.len());
+
+    let net_profit: Decimal = equity.last().map(|(_, eq)| *eq).unwrap_or(Decimal::ZERO);
+    let recovery = compute_recovery(net_profit, drawdown_aux.max_dd_abs);
+
+    MetricsResponseBody {
+        from,
+        to,
+        currency: currency.to_string(),
+        drawdown: drawdown_aux.metrics,
+        sharpe,
+        expectancy,
+        recovery,
+        profit_factor: pf,
+    }
+}
+
+// Resamples drawn for the bootstrap p-value, seeded so a response is reproducible
+const BOOTSTRAP_SAMPLES: usize = 5_000;
+const BOOTSTRAP_SEED: u64 = 0x5eed_da7a_f00d_7e57;
+
+fn mean_and_variance(values: &[f64]) -> Option<(f64, f64)> {
+    if values.len() < 2 {
+        return None;
+    }
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    let var = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1.0);
+    Some((mean, var))
+}
+
+fn welch_t_test(a: &[f64], b: &[f64]) -> Option<WelchTest> {
+    let (mean_a, var_a) = mean_and_variance(a)?;
+    let (mean_b, var_b) = mean_and_variance(b)?;
+    let se_a = var_a / a.len() as f64;
+    let se_b = var_b / b.len() as f64;
+    if se_a + se_b <= 0.0 {
+        return None;
+    }
+
+    let t = (mean_b - mean_a) / (se_a + se_b).sqrt();
+    let df = (se_a + se_b).powi(2)
+        / (se_a.powi(2) / (a.len() - 1) as f64 + se_b.powi(2) / (b.len() - 1) as f64);
+    let dist = StudentsT::new(0.0, 1.0, df).ok()?;
+    let p_value = (2.0 * dist.sf(t.abs())).min(1.0);
+
+    Some(WelchTest { t, df, p_value })
+}
+
+// SplitMix64, enough for resampling indices without pulling in an RNG crate
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_index(&mut self, len: usize) -> usize {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        ((z ^ (z >> 31)) % len as u64) as usize
+    }
+}
+
+// Both samples are shifted to the pooled mean so resampling happens under the
+// null hypothesis, the p-value is the share of resamples at least as extreme
+fn bootstrap_p_value(a: &[f64], b: &[f64], samples: usize, seed: u64) -> Option<BootstrapTest> {
+    if a.is_empty() || b.is_empty() || samples == 0 {
+        return None;
+    }
+    let mean = |values: &[f64]| values.iter().sum::<f64>() / values.len() as f64;
+    let (mean_a, mean_b) = (mean(a), mean(b));
+    let pooled = (a.iter().sum::<f64>() + b.iter().sum::<f64>()) / (a.len() + b.len()) as f64;
+    let shifted_a: Vec<f64> = a.iter().map(|v| v - mean_a + pooled).collect();
+    let shifted_b: Vec<f64> = b.iter().map(|v| v - mean_b + pooled).collect();
+    let observed = (mean_b - mean_a).abs();
+
+    let mut rng = SplitMix64(seed);
+    let mut resample_mean = |values: &[f64]| {
+        (0..values.len()).map(|_| values[rng.next_index(values.len())]).sum::<f64>() / values.len() as f64
+    };
+    let extreme = (0..samples)
+        .filter(|_| {
+            let diff = resample_mean(&shifted_b) - resample_mean(&shifted_a);
+            diff.abs() >= observed - f64::EPSILON * observed.max(1.0)
+        })
+        .count();
+
+    Some(BootstrapTest { samples, p_value: (extreme + 1) as f64 / (samples + 1) as f64 })
+}
+
+fn metric_delta(base: Option<f64>, compared: Option<f64>) -> MetricDelta {
+    let absolute = base.zip(compared).map(|(b, c)| c - b);
+    let relative = base.zip(absolute).filter(|(b, _)| *b != 0.0).map(|(b, d)| d / b.abs());
+    MetricDelta { base, compared, absolute, relative }
+}
+
+fn metric_deltas(base: &MetricsResponseBody, compared: &MetricsResponseBody) -> BTreeMap<String, MetricDelta> {
+    let values = |m: &MetricsResponseBody| -> Vec<(&'static str, Option<f64>)> {
+        vec![
+            ("net_profit", m.recovery.net_profit.to_f64()),
+            ("gross_profit", m.profit_factor.gross_profit.to_f64()),
+            ("gross_loss", m.profit_factor.gross_loss.to_f64()),
+            ("trade_count", Some(m.profit_factor.trade_count as f64)),
+            ("win_rate", m.expectancy.win_rate),
+            ("expectancy_usd", m.expectancy.expectancy_usd.to_f64()),
+            ("median_usd", m.expectancy.median_usd.to_f64()),
+            ("avg_win", m.expectancy.avg_win.to_f64()),
+            ("avg_loss", m.expectancy.avg_loss.to_f64()),
+            ("profit_factor", m.profit_factor.profit_factor),
+            ("max_dd_abs", m.drawdown.max_dd_abs.to_f64()),
+            ("max_dd_pct_base", Some(m.drawdown.max_dd_pct_base)),
+            ("sharpe", m.sharpe.sharpe),
+            ("recovery_factor", m.recovery.recovery_factor),
+        ]
+    };
+
+    values(base)
+        .into_iter()
+        .zip(values(compared))
+        .map(|((name, b), (_, c))| (name.to_string(), metric_delta(b, c)))
+        .collect()
+}
+
+// The bootstrap resamples thousands of times, so it runs off the async workers
+async fn compare_metrics(
+    base: &MetricsResponseBody,
+    base_rows: &[Strategy],
+    compared: MetricsResponseBody,
+    compared_rows: &[Strategy],
+) -> Result<MetricsComparison, AppError> {
+    let nets = |rows: &[Strategy]| -> Vec<f64> {
+        derive_nets(rows).nets.iter().filter_map(|n| n.to_f64()).collect()
+    };
+    let (a, b) = (nets(base_rows), nets(compared_rows));
+    let mean = |values: &[f64]| (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64);
+    let (mean_base, mean_compared, welch) = (mean(&a), mean(&b), welch_t_test(&a, &b));
+
+    let bootstrap = tokio::task::spawn_blocking(move || bootstrap_p_value(&a, &b, BOOTSTRAP_SAMPLES, BOOTSTRAP_SEED))
+        .await
+        .map_err(|e| AppError::Internal(format!("Bootstrap test failed: {e}")))?;
+
+    Ok(MetricsComparison {
+        deltas: metric_deltas(base, &compared),
+        significance: SignificanceMetrics { mean_base, mean_compared, welch, bootstrap },
+        metrics: compared,
+    })
+}
+
+// Closed strategies matching the filter, converted to the reporting currency
+pub(crate) async fn fetch_closed(state: &AppState, filter: &MetricsFilter) -> Result<Vec<Strategy>, AppError> {
+    let query = r#"
+    SELECT
+        *
+    FROM
+        strategy
+    WHERE
+        (exit_time AT TIME ZONE $5)::date >= $1
+    AND (exit_time AT TIME ZONE $5)::date <= $2
+    AND status = $3
+    AND ($4::text IS NULL OR account->>'account_id' = $4)
+    AND ($6::text[] IS NULL OR EXISTS (
+        SELECT 1 FROM journal j WHERE j.local_id = strategy.local_id AND j.tags && $6
+    ))
+    AND ($7::text IS NULL OR metadata->>'type' = $7)
+    "#;
+
+    let mut rows = sqlx::query_as::<_, Strategy>(query)
+        .bind(filter.from)
+        .bind(filter.to)
+        .bind(Into::<i32>::into(Status::Closed))
+        .bind(&filter.account_id)
+        .bind(state.calendar.timezone_name())
+        .bind(parse_tags(&filter.tags))
+        .bind(filter.strategy_type.map(|t| t.to_string()))
+        .fetch_all(&state.db.pool)
+        .observe("closed_strategies")
+        .await
+        .map_err(AppError::DatabaseError)?;
+
+    state.fx.to_reporting(&mut rows, &state.calendar)?;
+    Ok(rows)
+}
+
+#[utoipa::path(
+    get,
+    path = "/metrics",
+    tag = "analytics",
+    params(MetricsRequest, ("view" = Option<String>, Query, description = "Saved view id or name supplying defaults")),
+    responses((status = 200, description = "Risk-adjusted metrics, optionally compared and per account", body = MetricsBody)),
+)]
 pub(crate) async fn metrics(
-    Query(request): Query<MetricsRequest>,
+    ViewQuery(request): ViewQuery<MetricsRequest>,
     axum::extract::State(state): axum::extract::State<Arc<AppState>>,
 ) -> impl IntoResponse {
-    let status = Status::Closed;
-
-    let result: Result<Vec<Strategy>, AppError> = {
-        let query = r#"
-        SELECT
-            *
-        FROM
-            strategy
-        WHERE
-            exit_time::date >= $1
-        AND exit_time::date <= $2
-        AND status = $3
-        "#;
-
-        sqlx::query_as::<_, Strategy>(query)
-            .bind(request.from)
-            .bind(request.to)
-            .bind(Into::<i32>::into(status))
-            .fetch_all(&state.db.pool)
-            .await
-            .map_err(AppError::DatabaseError)
+    let rows = match fetch_closed(&state, &request.filter()).await {
+        Ok(rows) => rows,
+        Err(e) => return e.into_response(),
     };
+    let compared = match request.comparison_filter() {
+        Some(filter) => match fetch_closed(&state, &filter).await {
+            Ok(compared_rows) => Some((filter, compared_rows)),
+            Err(e) => return e.into_response(),
+        },
+        None => None,
+    };
+
+    let currency = state.fx.reporting_currency();
+    let body = build_metrics(request.from, request.to, currency, &state.calendar, &rows);
+
+    info!("Metrics: {}", json!(body));
 
-    match result {
-        Err(e) => e.into_response(),
-        Ok(rows) => {
-            // Build inputs
-            let NetsSummary { nets, wins_sum, losses_sum_abs, wins_count, losses_count } = derive_nets(&rows);
-            let daily = daily_from_rows(request.from, request.to, &rows);
-            let equity = equity_from_daily(&daily);
-
-            // Compute metrics
-            let drawdown_aux = compute_drawdown(&equity);
-            // Pull risk-free rate from the most recent available account snapshot in the set
-            let rf_annual = rows
-                .iter()
-                .max_by_key(|s| s.exit_time)
-                .map(|s| s.account.risk_free_annual)
-                .unwrap_or(0.0_f64);
-            let sharpe = compute_sharpe(&daily, rf_annual);
-            let expectancy = compute_expectancy(&nets, wins_sum, wins_count, losses_sum_abs, losses_count);
-            let pf = compute_profit_factor(wins_sum, losses_sum_abs, wins_count, losses_count, nets.len());
-
-            let net_profit: Decimal = equity.last().map(|(_, eq)| *eq).unwrap_or(Decimal::ZERO);
-            let recovery = compute_recovery(net_profit, drawdown_aux.max_dd_abs);
-
-            let body = MetricsResponseBody {
-                from: request.from,
-                to: request.to,
-                drawdown: drawdown_aux.metrics,
-                sharpe,
-                expectancy,
-                recovery,
-                profit_factor: pf,
-            };
-
-            let response = Json(json!({
-                "metrics": body
-            }));
-
-            info!("Metrics: {}", json!(body));
-
-            (StatusCode::OK, response).into_response()
+    let mut response = json!({
+        "metrics": body
+    });
+
+    if let Some((filter, compared_rows)) = compared {
+        let compared_body = build_metrics(filter.from, filter.to, currency, &state.calendar, &compared_rows);
+        match compare_metrics(&body, &rows, compared_body, &compared_rows).await {
+            Ok(comparison) => response["comparison"] = json!(comparison),
+            Err(e) => return e.into_response(),
         }
     }
+
+    if request.per_account {
+        let mut by_account: BTreeMap<String, Vec<Strategy>> = BTreeMap::new();
+        for s in rows {
+            by_account.entry(s.account.account_id.clone()).or_default().push(s);
+        }
+        let accounts: BTreeMap<String, MetricsResponseBody> = by_account
+            .iter()
+            .map(|(id, rows)| (id.clone(), build_metrics(request.from, request.to, currency, &state.calendar, rows)))
+            .collect();
+        response["accounts"] = json!(accounts);
+    }
+
+    (StatusCode::OK, Json(response)).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exits_after_the_last_session_keep_their_pnl() {
+        use rust_decimal_macros::dec;
+
+        let date = |day| chrono::NaiveDate::from_ymd_opt(2025, 3, day).unwrap();
+        let exit = |ts: &str| {
+            let mut strategy = Strategy::fixture();
+            strategy.exit_time = ts.parse().unwrap();
+            strategy.risk.stats.pnl = dec!(100);
+            strategy
+        };
+        // Friday session close and a Saturday exit, with the range ending on Sunday
+        let rows = vec![exit("2025-03-14T19:00:00Z"), exit("2025-03-15T16:00:00Z")];
+
+        let daily = daily_from_rows(date(10), date(16), &TradingCalendar::default(), &rows);
+        assert_eq!(daily.get(&date(14)), Some(&dec!(100)));
+        assert_eq!(daily.get(&date(17)), Some(&dec!(100)));
+        assert_eq!(daily.values().copied().sum::<Decimal>(), dec!(200));
+    }
+
+    #[test]
+    fn test_welch_t_test_matches_hand_computed_values() {
+        // means 3 and 6.583, variances 2.5 and 4.042, two-sided p from t-tables
+        let a = [1.0, 2.0, 3.0, 4.0, 5.0];
+        let b = [4.0, 5.0, 6.0, 7.0, 8.0, 9.5];
+        let welch = welch_t_test(&a, &b).unwrap();
+        assert!((welch.t - 3.3077).abs() < 1e-3);
+        assert!((welch.df - 8.9877).abs() < 1e-3);
+        assert!((welch.p_value - 0.0091).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_bootstrap_p_value_separates_shifted_samples() {
+        let a: Vec<f64> = (0..40).map(|i| (i % 7) as f64).collect();
+        let same = bootstrap_p_value(&a, &a, 2_000, BOOTSTRAP_SEED).unwrap();
+        let shifted: Vec<f64> = a.iter().map(|v| v + 5.0).collect();
+        let apart = bootstrap_p_value(&a, &shifted, 2_000, BOOTSTRAP_SEED).unwrap();
+
+        assert!(same.p_value > 0.9);
+        assert!(apart.p_value < 0.01);
+    }
 }
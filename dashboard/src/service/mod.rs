@@ -1,8 +1,21 @@
+pub mod account;
+pub mod audit;
+pub mod benchmark;
 pub mod common;
+pub mod correlation;
+pub mod distribution;
+pub mod envelope;
+pub mod events;
+pub mod extract;
+pub mod fees;
 pub mod health;
+pub mod journal;
 pub mod metrics;
 pub mod performance;
+pub mod positions;
+pub mod risk;
 pub mod symbols;
 pub mod strategy;
 pub mod universe;
+pub mod views;
 pub mod watermarks;
@@ -1,4 +1,4 @@
-use axum::{extract::Query, extract::State, response::IntoResponse};
+use axum::{extract::State, response::IntoResponse};
 use tracing::info;
 use std::sync::Arc;
 
@@ -10,10 +10,20 @@ use crate::{
     },
 };
 
+use crate::openapi::PerformanceBody;
 use super::common::AppError;
+use super::extract::ApiQuery;
+use crate::telemetry::ObserveQuery;
 
+#[utoipa::path(
+    get,
+    path = "/performance",
+    tag = "strategies",
+    params(PerformanceRequest),
+    responses((status = 200, description = "Per strategy P&L and ROI, amounts in the reporting currency", body = PerformanceBody)),
+)]
 pub(crate) async fn performance(
-    Query(request): Query<PerformanceRequest>,
+    ApiQuery(request): ApiQuery<PerformanceRequest>,
     State(state): State<Arc<AppState>>,
 ) -> impl IntoResponse {
     let query = r#"
@@ -22,9 +32,10 @@ pub(crate) async fn performance(
     FROM
         strategy
     WHERE
-        exit_time::date >= $1
-    AND exit_time::date <= $2
+        (exit_time AT TIME ZONE $5)::date >= $1
+    AND (exit_time AT TIME ZONE $5)::date <= $2
     AND status = $3
+    AND ($4::text IS NULL OR account->>'account_id' = $4)
     "#;
 
     let status = match request.is_active {
@@ -36,9 +47,13 @@ pub(crate) async fn performance(
         .bind(request.from)
         .bind(request.to)
         .bind(Into::<i32>::into(status))
+        .bind(request.account_id)
+        .bind(state.calendar.timezone_name())
         .fetch_all(&state.db.pool)
+        .observe("performance")
         .await
-        .map_err(AppError::DatabaseError);
+        .map_err(AppError::DatabaseError)
+        .and_then(|mut rows| state.fx.to_reporting(&mut rows, &state.calendar).map(|_| rows));
 
     match result {
         Ok(rows) => {
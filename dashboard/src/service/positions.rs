@@ -0,0 +1,87 @@
+use axum::{extract::State, response::IntoResponse};
+use chrono::Utc;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use crate::{
+    AppState,
+    models::{
+        position::{ExposureBucket, ExposureSummary, OpenPosition, PositionsResponse},
+        strategy::{Status, Strategy},
+    },
+};
+
+use crate::openapi::PositionsBody;
+use super::common::{AccountFilter, AppError};
+use super::extract::ApiQuery;
+use crate::telemetry::ObserveQuery;
+
+fn exposure_by<F>(positions: &[OpenPosition], key_of: F) -> Vec<ExposureBucket>
+where
+    F: Fn(&OpenPosition) -> String,
+{
+    let mut buckets: BTreeMap<String, ExposureBucket> = BTreeMap::new();
+    for p in positions {
+        let key = key_of(p);
+        let bucket = buckets.entry(key.clone()).or_insert_with(|| ExposureBucket {
+            key,
+            ..Default::default()
+        });
+        bucket.add(p);
+    }
+    buckets.into_values().collect()
+}
+
+#[utoipa::path(
+    get,
+    path = "/positions",
+    tag = "strategies",
+    params(AccountFilter),
+    responses((status = 200, description = "Open positions and exposure", body = PositionsBody)),
+)]
+pub(crate) async fn positions(
+    ApiQuery(filter): ApiQuery<AccountFilter>,
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    let query = r#"
+    SELECT
+        *
+    FROM
+        strategy
+    WHERE
+        status = $1
+    AND ($2::text IS NULL OR account->>'account_id' = $2)
+    ORDER BY
+        entry_time
+    "#;
+
+    let result = sqlx::query_as::<_, Strategy>(query)
+        .bind(Into::<i32>::into(Status::Open))
+        .bind(filter.account_id)
+        .fetch_all(&state.db.pool)
+        .observe("positions")
+        .await
+        .map_err(AppError::DatabaseError);
+
+    match result {
+        Ok(rows) => {
+            let as_of = Utc::now();
+            let positions: Vec<OpenPosition> =
+                rows.iter().map(|s| OpenPosition::new(s, as_of)).collect();
+
+            let exposure = ExposureSummary {
+                by_underlying: exposure_by(&positions, |p| p.underlying.clone()),
+                by_asset_type: exposure_by(&positions, |p| p.asset_type.to_string()),
+                by_side: exposure_by(&positions, |p| p.direction.to_string()),
+            };
+
+            PositionsResponse {
+                as_of,
+                response: positions,
+                exposure,
+            }
+            .into_response()
+        }
+        Err(e) => e.into_response(),
+    }
+}
@@ -0,0 +1,246 @@
+use axum::{extract::State, response::IntoResponse};
+use chrono::Utc;
+use rust_decimal::Decimal;
+use rust_decimal::prelude::ToPrimitive;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+use crate::{
+    AppState,
+    fx::FxRates,
+    models::{
+        risk_report::{ConcentrationRow, RiskReport},
+        settings::RiskLimits,
+        strategy::{Status, Strategy},
+    },
+};
+
+use crate::openapi::RiskBody;
+use super::common::{AccountFilter, AppError};
+use super::extract::ApiQuery;
+use crate::telemetry::ObserveQuery;
+
+fn share_of(value: Decimal, nlv: Decimal) -> Option<f64> {
+    if nlv > Decimal::ZERO {
+        (value / nlv).to_f64()
+    } else {
+        None
+    }
+}
+
+fn check_limits(row: &mut ConcentrationRow, limits: &RiskLimits) {
+    let Some(limit) = limits.limit_for(&row.key) else { return };
+
+    if let Some(max) = limit.max_positions
+        && row.positions > max
+    {
+        row.breaches.push(format!("positions {} > {}", row.positions, max));
+    }
+    if let (Some(max), Some(pct)) = (limit.max_exposure_pct, row.exposure_pct)
+        && pct > max
+    {
+        row.breaches.push(format!("exposure {pct:.4} > {max:.4} of NLV"));
+    }
+    if let (Some(max), Some(pct)) = (limit.max_net_premium_pct, row.net_premium_pct)
+        && pct.abs() > max
+    {
+        row.breaches.push(format!("net premium {:.4} > {max:.4} of NLV", pct.abs()));
+    }
+    row.limit = Some(limit.clone());
+}
+
+fn concentration_by<F>(
+    rows: &[Strategy],
+    nlv: Decimal,
+    limits: &RiskLimits,
+    fx: &FxRates,
+    key_of: F,
+) -> Result<Vec<ConcentrationRow>, AppError>
+where
+    F: Fn(&Strategy) -> String,
+{
+    let mut groups: BTreeMap<String, ConcentrationRow> = BTreeMap::new();
+    for s in rows {
+        let key = key_of(s);
+        let row = groups.entry(key.clone()).or_insert_with(|| ConcentrationRow {
+            key,
+            ..Default::default()
+        });
+        // Same notional as the /positions exposure, negative for credits received
+        let notional = fx.convert(
+            s.signed_notional(s.meta.open_price),
+            &s.account.currency,
+            s.entry_time.date_naive(),
+        )?;
+        row.positions += 1;
+        row.exposure += notional.abs();
+        row.net_premium -= notional;
+    }
+
+    Ok(groups
+        .into_values()
+        .map(|mut row| {
+            row.exposure_pct = share_of(row.exposure, nlv);
+            row.net_premium_pct = share_of(row.net_premium, nlv);
+            check_limits(&mut row, limits);
+            row
+        })
+        .collect())
+}
+
+fn risk_report(state: &AppState, rows: &[Strategy]) -> Result<RiskReport, AppError> {
+    // Latest snapshot per account attached to any open position, summed in
+    // the reporting currency for the consolidated view
+    let mut latest: HashMap<&str, &Strategy> = HashMap::new();
+    for s in rows {
+        let entry = latest.entry(s.account.account_id.as_str()).or_insert(s);
+        if s.account.date > entry.account.date {
+            *entry = s;
+        }
+    }
+    let fx = &state.fx;
+    let nlv = latest
+        .values()
+        .map(|s| fx.convert(s.account.net_liquidating_value, &s.account.currency, s.account.date))
+        .sum::<Result<Decimal, AppError>>()?;
+    let limits = &state.settings.risk;
+
+    let by_underlying = concentration_by(rows, nlv, limits, fx, |s| s.meta.underlying.clone())?;
+    // Strategy rows carry the aliased symbol, i.e. the futures root
+    let by_root = concentration_by(rows, nlv, limits, fx, |s| s.symbol.clone())?;
+
+    let breach_count = by_underlying
+        .iter()
+        .chain(by_root.iter())
+        .map(|row| row.breaches.len())
+        .sum();
+
+    Ok(RiskReport {
+        as_of: Utc::now(),
+        currency: fx.reporting_currency().to_string(),
+        net_liquidating_value: nlv,
+        by_underlying,
+        by_root,
+        breach_count,
+    })
+}
+
+#[utoipa::path(
+    get,
+    path = "/risk",
+    tag = "analytics",
+    params(AccountFilter),
+    responses((status = 200, description = "Concentration against the configured limits", body = RiskBody)),
+)]
+pub(crate) async fn risk(
+    ApiQuery(filter): ApiQuery<AccountFilter>,
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    let query = r#"
+    SELECT
+        *
+    FROM
+        strategy
+    WHERE
+        status = $1
+    AND ($2::text IS NULL OR account->>'account_id' = $2)
+    "#;
+
+    let result = sqlx::query_as::<_, Strategy>(query)
+        .bind(Into::<i32>::into(Status::Open))
+        .bind(filter.account_id)
+        .fetch_all(&state.db.pool)
+        .observe("risk")
+        .await
+        .map_err(AppError::DatabaseError)
+        .and_then(|rows| risk_report(&state, &rows));
+
+    match result {
+        Ok(report) => report.into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{PriceEffect, settings::UnderlyingLimit};
+    use rust_decimal_macros::dec;
+
+    fn open(underlying: &str, price_effect: PriceEffect, open_price: Decimal) -> Strategy {
+        let mut strategy = Strategy::fixture();
+        strategy.status = Status::Open;
+        strategy.meta.underlying = underlying.to_string();
+        strategy.meta.price_effect = price_effect;
+        strategy.meta.open_price = open_price;
+        strategy
+    }
+
+    fn limits(key: &str, limit: UnderlyingLimit) -> RiskLimits {
+        RiskLimits {
+            default: None,
+            underlyings: [(key.to_string(), limit)].into(),
+        }
+    }
+
+    #[test]
+    fn test_concentration_uses_notional_and_nets_premium() {
+        let rows = vec![
+            open("SPY", PriceEffect::Credit, dec!(2.00)),
+            open("SPY", PriceEffect::Debit, dec!(0.50)),
+            open("QQQ", PriceEffect::Credit, dec!(1.00)),
+        ];
+        let concentration = concentration_by(&rows, dec!(10000), &RiskLimits::default(), &FxRates::default(), |s| {
+            s.meta.underlying.clone()
+        })
+        .ok()
+        .unwrap();
+
+        let spy = concentration.iter().find(|row| row.key == "SPY").unwrap();
+        assert_eq!(spy.positions, 2);
+        assert_eq!(spy.exposure, dec!(250));
+        assert_eq!(spy.net_premium, dec!(150));
+        assert_eq!(spy.exposure_pct, Some(0.025));
+        assert!(spy.breaches.is_empty());
+    }
+
+    #[test]
+    fn test_limits_flag_every_breached_threshold() {
+        let rows = vec![open("SPY", PriceEffect::Credit, dec!(3.00)), open("SPY", PriceEffect::Credit, dec!(2.00))];
+        let limit = UnderlyingLimit {
+            max_positions: Some(1),
+            max_exposure_pct: Some(0.04),
+            max_net_premium_pct: Some(0.10),
+        };
+        let concentration =
+            concentration_by(&rows, dec!(10000), &limits("SPY", limit), &FxRates::default(), |s| s.meta.underlying.clone())
+                .ok()
+                .unwrap();
+
+        // $500 of credit against $10k NLV breaches positions and exposure but not net premium
+        let spy = &concentration[0];
+        assert_eq!(spy.breaches, vec!["positions 2 > 1", "exposure 0.0500 > 0.0400 of NLV"]);
+        assert!(spy.limit.is_some());
+    }
+
+    #[test]
+    fn test_unlimited_keys_and_zero_nlv_never_breach() {
+        let mut row = ConcentrationRow {
+            key: "QQQ".to_string(),
+            positions: 10,
+            ..Default::default()
+        };
+        check_limits(&mut row, &limits("SPY", UnderlyingLimit { max_positions: Some(1), ..Default::default() }));
+        assert!(row.breaches.is_empty());
+        assert!(row.limit.is_none());
+
+        let rows = vec![open("SPY", PriceEffect::Credit, dec!(1.00))];
+        let limit = UnderlyingLimit { max_exposure_pct: Some(0.01), ..Default::default() };
+        let concentration =
+            concentration_by(&rows, Decimal::ZERO, &limits("SPY", limit), &FxRates::default(), |s| s.meta.underlying.clone())
+                .ok()
+                .unwrap();
+        assert_eq!(concentration[0].exposure_pct, None);
+        assert!(concentration[0].breaches.is_empty());
+    }
+}
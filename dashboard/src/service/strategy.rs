@@ -1,4 +1,4 @@
-use axum::{extract::{Path, Query, State}, response::IntoResponse};
+use axum::{extract::State, response::IntoResponse};
 use std::sync::Arc;
 
 use crate::{
@@ -6,11 +6,33 @@ use crate::{
     models::strategy::{Strategy, StrategyResponse},
 };
 
-use super::common::{AppError, SimpleRequest};
+use crate::openapi::StrategiesBody;
+use super::common::{AppError, SimpleRequest, like_escape, parse_tags};
+use super::extract::{ApiPath, ApiQuery};
+use crate::telemetry::ObserveQuery;
 
+/// `LIKE` patterns for every contract that may trade under `symbol`. Contracts start
+/// with their root, futures options with a dot in front, so the prefix scan stays on
+/// the symbol index and callers keep only exact matches with `Strategy::traded_as`
+pub(crate) fn symbol_patterns(symbol: &str) -> Vec<String> {
+    let body = like_escape(symbol.trim_start_matches(['.', '/']));
+    if symbol.starts_with('/') || symbol.starts_with("./") {
+        vec![format!("/{body}%"), format!("./{body}%")]
+    } else {
+        vec![format!("{body}%")]
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/strategy/{symbol}",
+    tag = "strategies",
+    params(("symbol" = String, Path, description = "Root or contract, e.g. /ES or AAPL"), SimpleRequest),
+    responses((status = 200, description = "Strategies traded under the symbol, amounts in the reporting currency", body = StrategiesBody)),
+)]
 pub(crate) async fn strategy(
-    Path(symbol): Path<String>,
-    Query(request): Query<SimpleRequest>,
+    ApiPath(symbol): ApiPath<String>,
+    ApiQuery(request): ApiQuery<SimpleRequest>,
     State(state): State<Arc<AppState>>,
 ) -> impl IntoResponse {
     let query = r#"
@@ -19,21 +41,39 @@ pub(crate) async fn strategy(
     FROM
         strategy
     WHERE
-        symbol = $1
-    AND entry_time >= $2
-    AND exit_time <= $3
+        symbol LIKE ANY($1)
+    AND (entry_time AT TIME ZONE $6)::date >= $2
+    AND (exit_time AT TIME ZONE $6)::date <= $3
+    AND ($4::text IS NULL OR account->>'account_id' = $4)
+    AND ($5::text[] IS NULL OR EXISTS (
+        SELECT 1 FROM journal j WHERE j.local_id = strategy.local_id AND j.tags && $5
+    ))
     "#;
 
     let result = sqlx::query_as::<_, Strategy>(query)
-        .bind(symbol)
+        .bind(symbol_patterns(&symbol))
         .bind(request.from)
         .bind(request.to)
+        .bind(&request.account_id)
+        .bind(parse_tags(&request.tags))
+        .bind(state.calendar.timezone_name())
         .fetch_all(&state.db.pool)
+        .observe("strategy")
         .await
         .map_err(AppError::DatabaseError);
 
     match result {
-        Ok(rows) => StrategyResponse { response: rows }.into_response(),
+        Ok(rows) => {
+            // A root such as /ES spans every contract roll, anything else is an exact contract
+            let mut response: Vec<Strategy> = rows
+                .into_iter()
+                .filter(|s| s.traded_as(&symbol))
+                .collect();
+            match state.fx.to_reporting(&mut response, &state.calendar) {
+                Ok(()) => StrategyResponse { response }.into_response(),
+                Err(e) => e.into_response(),
+            }
+        }
         Err(e) => e.into_response(),
     }
 }
@@ -1,31 +1,158 @@
 use axum::{extract::State, response::IntoResponse};
+use std::collections::BTreeMap;
 use std::sync::Arc;
 
 use crate::{
     AppState,
-    models::symbol::{Symbol, SymbolResponse},
+    models::{
+        strategy::Status,
+        symbol::{Symbol, SymbolActivity, SymbolRequest, SymbolResponse, SymbolSort},
+    },
+    symbology,
 };
 
-use super::common::AppError;
+use crate::openapi::SymbolsBody;
+use super::common::{AppError, like_escape};
+use super::extract::ApiQuery;
+use crate::telemetry::ObserveQuery;
 
-pub(crate) async fn symbols(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+fn sort_symbols(symbols: &mut [Symbol], sort: SymbolSort) {
+    match sort {
+        SymbolSort::Active => symbols.sort_by(|a, b| {
+            b.open_positions
+                .cmp(&a.open_positions)
+                .then(b.last_trade.cmp(&a.last_trade))
+                .then(a.name.cmp(&b.name))
+        }),
+        SymbolSort::Name => symbols.sort_by(|a, b| a.name.cmp(&b.name)),
+        SymbolSort::LastTrade => symbols.sort_by_key(|s| std::cmp::Reverse(s.last_trade)),
+        SymbolSort::TradeCount => symbols.sort_by_key(|s| std::cmp::Reverse(s.trade_count)),
+        SymbolSort::Pnl => symbols.sort_by_key(|s| std::cmp::Reverse(s.net_pnl)),
+    }
+}
+
+// Roots aggregated over their contracts, P&L in the reporting currency
+pub(crate) async fn fetch_symbols(state: &AppState, request: &SymbolRequest) -> Result<Vec<Symbol>, AppError> {
     let query = r#"
-    SELECT 
-        Distinct(symbol)
-    FROM 
+    SELECT
+        symbol,
+        account->>'currency' AS currency,
+        MIN(entry_time) AS first_trade,
+        MAX(GREATEST(entry_time, exit_time)) AS last_trade,
+        COUNT(*) AS trade_count,
+        COUNT(*) FILTER (WHERE status = $2) AS open_count,
+        COUNT(*) FILTER (WHERE status = $3) AS closed_count,
+        COUNT(*) FILTER (
+            WHERE status = $3
+            AND ((risk->>'stats')::jsonb->>'pnl')::numeric
+                - COALESCE(((risk->>'stats')::jsonb->>'fee')::numeric, 0) > 0
+        ) AS wins,
+        COALESCE(SUM(
+            ((risk->>'stats')::jsonb->>'pnl')::numeric
+                - COALESCE(((risk->>'stats')::jsonb->>'fee')::numeric, 0)
+        ) FILTER (WHERE status = $3), 0) AS net_pnl,
+        array_remove(array_agg(DISTINCT metadata->>'asset_type'), NULL) AS asset_types
+    FROM
         strategy
+    WHERE
+        ($1::text IS NULL OR account->>'account_id' = $1)
+    AND ($4::text IS NULL OR upper(ltrim(symbol, './')) LIKE $4 || '%')
+    GROUP BY
+        symbol,
+        account->>'currency'
     "#;
 
-    let result = sqlx::query_as::<_, Symbol>(query)
+    // Contracts start with their root, so matching contracts also covers root names
+    let prefix = request
+        .prefix
+        .as_deref()
+        .map(|p| like_escape(&p.trim_start_matches(['.', '/']).to_uppercase()));
+
+    let rows = sqlx::query_as::<_, SymbolActivity>(query)
+        .bind(&request.account_id)
+        .bind(Into::<i32>::into(Status::Open))
+        .bind(Into::<i32>::into(Status::Closed))
+        .bind(prefix)
         .fetch_all(&state.db.pool)
+        .observe("symbol_activity")
         .await
-        .map_err(AppError::DatabaseError);
+        .map_err(AppError::DatabaseError)?;
 
-    match result {
-        Ok(rows) => SymbolResponse {
-            response: rows.iter().map(Symbol::from).collect(),
-        }
-        .into_response(),
+    let parser = symbology::parser();
+    let mut roots: BTreeMap<String, Symbol> = BTreeMap::new();
+    for activity in &rows {
+        let contract = parser.parse(&activity.symbol, activity.first_trade.date_naive());
+        let net_pnl = state.fx.convert(
+            activity.net_pnl,
+            activity.currency.as_deref().unwrap_or_default(),
+            state.calendar.booking_date(activity.last_trade),
+        )?;
+        roots
+            .entry(contract.root.clone())
+            .or_insert_with(|| Symbol::new(contract.root.clone(), activity.first_trade, activity.last_trade))
+            .accumulate(activity, contract, net_pnl);
+    }
+
+    // Cheap once the query has narrowed the rows, keeps the matching rules in one place
+    let mut symbols: Vec<Symbol> = roots
+        .into_values()
+        .filter(|s| request.prefix.as_deref().is_none_or(|p| s.matches_prefix(p)))
+        .collect();
+    sort_symbols(&mut symbols, request.sort);
+    Ok(symbols)
+}
+
+#[utoipa::path(
+    get,
+    path = "/symbols",
+    tag = "strategies",
+    params(SymbolRequest),
+    responses((status = 200, description = "Traded roots with their contracts and activity", body = SymbolsBody)),
+)]
+pub(crate) async fn symbols(
+    ApiQuery(request): ApiQuery<SymbolRequest>,
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    match fetch_symbols(&state, &request).await {
+        Ok(response) => SymbolResponse { response }.into_response(),
         Err(e) => e.into_response(),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+    use rust_decimal_macros::dec;
+
+    fn symbol(name: &str, open_positions: i64, last_day: u32, net_pnl: rust_decimal::Decimal) -> Symbol {
+        let last_trade = Utc.with_ymd_and_hms(2025, 3, last_day, 15, 0, 0).unwrap();
+        let mut symbol = Symbol::new(name.to_string(), last_trade, last_trade);
+        symbol.open_positions = open_positions;
+        symbol.net_pnl = net_pnl;
+        symbol
+    }
+
+    fn names(symbols: &[Symbol]) -> Vec<&str> {
+        symbols.iter().map(|s| s.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_sort_orders() {
+        let mut symbols = vec![
+            symbol("SPY", 0, 12, dec!(50)),
+            symbol("/ES", 2, 10, dec!(-20)),
+            symbol("AAPL", 0, 14, dec!(10)),
+            symbol("QQQ", 2, 11, dec!(5)),
+        ];
+
+        sort_symbols(&mut symbols, SymbolSort::Active);
+        assert_eq!(names(&symbols), ["QQQ", "/ES", "AAPL", "SPY"]);
+        sort_symbols(&mut symbols, SymbolSort::Name);
+        assert_eq!(names(&symbols), ["/ES", "AAPL", "QQQ", "SPY"]);
+        sort_symbols(&mut symbols, SymbolSort::LastTrade);
+        assert_eq!(names(&symbols), ["AAPL", "SPY", "QQQ", "/ES"]);
+        sort_symbols(&mut symbols, SymbolSort::Pnl);
+        assert_eq!(names(&symbols), ["SPY", "AAPL", "QQQ", "/ES"]);
+    }
+}
@@ -1,35 +1,84 @@
-use axum::{extract::Query, extract::State, response::IntoResponse};
+use axum::{extract::State, response::IntoResponse};
 use std::sync::Arc;
 
 use crate::{
     AppState,
-    models::strategy::{Strategy, StrategyResponse},
+    models::strategy::{Status, Strategy, StrategyResponse, StrategyType},
 };
 
-use super::common::SimpleRequest;
+use crate::openapi::StrategiesBody;
+use super::common::{SimpleRequest, parse_tags};
 use super::common::AppError;
+use super::strategy::symbol_patterns;
+use super::views::ViewQuery;
+use crate::telemetry::ObserveQuery;
 
-pub(crate) async fn universe(
-    Query(request): Query<SimpleRequest>,
-    State(state): State<Arc<AppState>>,
-) -> impl IntoResponse {
+// Narrows the universe beyond the range, account and tags of the request
+#[derive(Default)]
+pub(crate) struct UniverseFilter {
+    // Root or exact contract
+    pub symbol: Option<String>,
+    pub status: Option<Status>,
+    pub strategy_type: Option<StrategyType>,
+}
+
+// Strategies entered and exited within the range, in their own currency
+pub(crate) async fn fetch_universe(
+    state: &AppState,
+    request: &SimpleRequest,
+    filter: &UniverseFilter,
+) -> Result<Vec<Strategy>, AppError> {
     let query = r#"
     SELECT
         *
     FROM
         strategy
     WHERE
-        entry_time >= $1
-    AND exit_time <= $2
+        (entry_time AT TIME ZONE $8)::date >= $1
+    AND (exit_time AT TIME ZONE $8)::date <= $2
+    AND ($3::text IS NULL OR account->>'account_id' = $3)
+    AND ($4::text[] IS NULL OR EXISTS (
+        SELECT 1 FROM journal j WHERE j.local_id = strategy.local_id AND j.tags && $4
+    ))
+    AND ($5::text[] IS NULL OR symbol LIKE ANY($5))
+    AND ($6::int IS NULL OR status = $6)
+    AND ($7::text IS NULL OR metadata->>'type' = $7)
     "#;
 
-    let result = sqlx::query_as::<_, Strategy>(query)
+    let mut rows = sqlx::query_as::<_, Strategy>(query)
         .bind(request.from)
         .bind(request.to)
+        .bind(&request.account_id)
+        .bind(parse_tags(&request.tags))
+        .bind(filter.symbol.as_deref().map(symbol_patterns))
+        .bind(filter.status.map(Into::<i32>::into))
+        .bind(filter.strategy_type.map(|t| t.to_string()))
+        .bind(state.calendar.timezone_name())
         .fetch_all(&state.db.pool)
+        .observe("universe")
         .await
-        .map_err(AppError::DatabaseError);
+        .map_err(AppError::DatabaseError)?;
+
+    if let Some(symbol) = &filter.symbol {
+        rows.retain(|s| s.traded_as(symbol));
+    }
+    Ok(rows)
+}
 
+#[utoipa::path(
+    get,
+    path = "/universe",
+    tag = "strategies",
+    params(SimpleRequest, ("view" = Option<String>, Query, description = "Saved view id or name supplying defaults")),
+    responses((status = 200, description = "Every strategy in the range, amounts in the reporting currency", body = StrategiesBody)),
+)]
+pub(crate) async fn universe(
+    ViewQuery(request): ViewQuery<SimpleRequest>,
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    let result = fetch_universe(&state, &request, &UniverseFilter::default())
+        .await
+        .and_then(|mut rows| state.fx.to_reporting(&mut rows, &state.calendar).map(|_| rows));
     match result {
         Ok(rows) => StrategyResponse { response: rows }.into_response(),
         Err(e) => e.into_response(),
@@ -0,0 +1,346 @@
+use axum::{
+    extract::{FromRequestParts, MatchedPath, State},
+    http::{StatusCode, request::Parts},
+    response::IntoResponse,
+};
+use serde::de::DeserializeOwned;
+use sqlx::types::Uuid;
+use std::sync::Arc;
+
+use crate::{
+    AppState,
+    auth::{AdminOnly, Principal},
+    models::view::{SavedView, SavedViewRequest, SavedViewResponse, ViewListRequest},
+};
+
+use crate::openapi::ViewsBody;
+use super::common::AppError;
+use super::envelope::V1_PREFIX;
+use super::extract::{ApiJson, ApiPath, ApiQuery, deserialize_query};
+use crate::telemetry::ObserveQuery;
+
+// Routes whose query string accepts a view parameter
+pub const VIEW_ENDPOINTS: &[&str] = &["/metrics", "/universe"];
+
+const VIEW_PARAM: &str = "view";
+
+/// Query extractor that expands `view=<id or name>` into the stored filters,
+/// parameters given explicitly in the URL take precedence over the view's
+pub(crate) struct ViewQuery<T>(pub T);
+
+impl<T> FromRequestParts<Arc<AppState>> for ViewQuery<T>
+where
+    T: DeserializeOwned,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &Arc<AppState>) -> Result<Self, Self::Rejection> {
+        let raw = parts.uri.query().unwrap_or_default();
+        let mut pairs: Vec<(String, String)> = serde_urlencoded::from_str(raw)
+            .map_err(|e| AppError::InvalidQuery(format!("Invalid query string: {e}")))?;
+
+        if let Some(index) = pairs.iter().position(|(key, _)| key == VIEW_PARAM) {
+            let (_, reference) = pairs.remove(index);
+            let view = find_view(state, &reference).await?;
+            let route = parts.extensions.get::<MatchedPath>().map(MatchedPath::as_str).unwrap_or_default();
+            apply_view(&mut pairs, &view, route)?;
+        }
+
+        let expanded = serde_urlencoded::to_string(&pairs)
+            .map_err(|e| AppError::InvalidQuery(format!("Invalid query string: {e}")))?;
+        deserialize_query(&expanded, &state.settings.api).map(ViewQuery)
+    }
+}
+
+// A view only carries parameters for the route it was saved from
+fn apply_view(pairs: &mut Vec<(String, String)>, view: &SavedView, route: &str) -> Result<(), AppError> {
+    let route = route.strip_prefix(V1_PREFIX).unwrap_or(route);
+    if view.endpoint != route {
+        return Err(AppError::BadRequest(format!(
+            "View '{}' was saved for {}, not {route}",
+            view.name, view.endpoint
+        )));
+    }
+    for (key, value) in view.query_pairs() {
+        if key != VIEW_PARAM && !pairs.iter().any(|(k, _)| *k == key) {
+            pairs.push((key, value));
+        }
+    }
+    Ok(())
+}
+
+async fn find_view(state: &AppState, reference: &str) -> Result<SavedView, AppError> {
+    if let Ok(id) = Uuid::parse_str(reference) {
+        return sqlx::query_as::<_, SavedView>("SELECT * FROM saved_view WHERE id = $1")
+            .bind(id)
+            .fetch_optional(&state.db.pool)
+            .observe("view_by_id")
+            .await
+            .map_err(AppError::DatabaseError)?
+            .ok_or_else(|| view_not_found(reference));
+    }
+
+    let mut views = sqlx::query_as::<_, SavedView>("SELECT * FROM saved_view WHERE name = $1")
+        .bind(reference)
+        .fetch_all(&state.db.pool)
+        .observe("view_by_name")
+        .await
+        .map_err(AppError::DatabaseError)?;
+
+    match views.len() {
+        0 => Err(view_not_found(reference)),
+        1 => Ok(views.remove(0)),
+        _ => Err(AppError::BadRequest(format!(
+            "View name '{reference}' is used by several owners, reference it by id"
+        ))),
+    }
+}
+
+fn view_not_found(reference: &str) -> AppError {
+    AppError::NotFound(format!("No saved view {reference}"))
+}
+
+fn validate(request: &SavedViewRequest) -> Result<(), AppError> {
+    if request.name.trim().is_empty() || request.owner.trim().is_empty() {
+        return Err(AppError::BadRequest("View name and owner are required".to_string()));
+    }
+    if !VIEW_ENDPOINTS.contains(&request.endpoint.as_str()) {
+        return Err(AppError::BadRequest(format!(
+            "Views are supported on {}, not {}",
+            VIEW_ENDPOINTS.join(", "),
+            request.endpoint
+        )));
+    }
+    if request.params.contains_key(VIEW_PARAM) {
+        return Err(AppError::BadRequest("A view cannot reference another view".to_string()));
+    }
+    Ok(())
+}
+
+fn default_owner(request: &mut SavedViewRequest, principal: &Principal) {
+    if request.owner.trim().is_empty() {
+        request.owner = principal.subject.clone();
+    }
+}
+
+fn name_conflict(request: &SavedViewRequest) -> AppError {
+    AppError::Conflict(format!("{} already has a view named '{}'", request.owner, request.name))
+}
+
+#[utoipa::path(
+    get,
+    path = "/views",
+    operation_id = "list_views",
+    tag = "views",
+    params(ViewListRequest),
+    responses((status = 200, description = "Saved views", body = ViewsBody)),
+)]
+pub(crate) async fn list(
+    ApiQuery(request): ApiQuery<ViewListRequest>,
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    let query = r#"
+    SELECT
+        *
+    FROM
+        saved_view
+    WHERE
+        ($1::text IS NULL OR owner = $1)
+        AND ($2::text IS NULL OR endpoint = $2)
+    ORDER BY
+        owner,
+        name
+    "#;
+
+    let result = sqlx::query_as::<_, SavedView>(query)
+        .bind(&request.owner)
+        .bind(&request.endpoint)
+        .fetch_all(&state.db.pool)
+        .observe("view_list")
+        .await
+        .map_err(AppError::DatabaseError);
+
+    match result {
+        Ok(rows) => SavedViewResponse { response: rows }.into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/views/{id}",
+    operation_id = "get_view",
+    tag = "views",
+    params(("id" = Uuid, Path, description = "Saved view id")),
+    responses((status = 200, description = "The saved view", body = ViewsBody)),
+)]
+pub(crate) async fn get(ApiPath(id): ApiPath<Uuid>, State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    match find_view(&state, &id.to_string()).await {
+        Ok(view) => SavedViewResponse { response: vec![view] }.into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/views",
+    operation_id = "create_view",
+    tag = "views",
+    request_body = SavedViewRequest,
+    responses((status = 201, description = "View saved, admin only", body = ViewsBody)),
+)]
+pub(crate) async fn create(
+    AdminOnly(principal): AdminOnly,
+    State(state): State<Arc<AppState>>,
+    ApiJson(mut request): ApiJson<SavedViewRequest>,
+) -> impl IntoResponse {
+    default_owner(&mut request, &principal);
+    if let Err(e) = validate(&request) {
+        return e.into_response();
+    }
+
+    let query = r#"
+    INSERT INTO saved_view (id, name, endpoint, params, owner)
+    VALUES ($1, $2, $3, $4, $5)
+    RETURNING *
+    "#;
+
+    let result = sqlx::query_as::<_, SavedView>(query)
+        .bind(Uuid::new_v4())
+        .bind(request.name.trim())
+        .bind(&request.endpoint)
+        .bind(sqlx::types::Json(&request.params))
+        .bind(request.owner.trim())
+        .fetch_one(&state.db.pool)
+        .observe_one("view_create")
+        .await;
+
+    match result {
+        Ok(view) => (StatusCode::CREATED, SavedViewResponse { response: vec![view] }).into_response(),
+        Err(sqlx::Error::Database(e)) if e.is_unique_violation() => name_conflict(&request).into_response(),
+        Err(e) => AppError::DatabaseError(e).into_response(),
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/views/{id}",
+    operation_id = "update_view",
+    tag = "views",
+    params(("id" = Uuid, Path, description = "Saved view id")),
+    request_body = SavedViewRequest,
+    responses((status = 200, description = "View replaced, admin only", body = ViewsBody)),
+)]
+pub(crate) async fn update(
+    AdminOnly(principal): AdminOnly,
+    ApiPath(id): ApiPath<Uuid>,
+    State(state): State<Arc<AppState>>,
+    ApiJson(mut request): ApiJson<SavedViewRequest>,
+) -> impl IntoResponse {
+    default_owner(&mut request, &principal);
+    if let Err(e) = validate(&request) {
+        return e.into_response();
+    }
+
+    let query = r#"
+    UPDATE saved_view
+    SET
+        name = $2,
+        endpoint = $3,
+        params = $4,
+        owner = $5,
+        updated_at = now()
+    WHERE
+        id = $1
+    RETURNING *
+    "#;
+
+    let result = sqlx::query_as::<_, SavedView>(query)
+        .bind(id)
+        .bind(request.name.trim())
+        .bind(&request.endpoint)
+        .bind(sqlx::types::Json(&request.params))
+        .bind(request.owner.trim())
+        .fetch_optional(&state.db.pool)
+        .observe("view_update")
+        .await;
+
+    match result {
+        Ok(Some(view)) => SavedViewResponse { response: vec![view] }.into_response(),
+        Ok(None) => view_not_found(&id.to_string()).into_response(),
+        Err(sqlx::Error::Database(e)) if e.is_unique_violation() => name_conflict(&request).into_response(),
+        Err(e) => AppError::DatabaseError(e).into_response(),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/views/{id}",
+    operation_id = "delete_view",
+    tag = "views",
+    params(("id" = Uuid, Path, description = "Saved view id")),
+    responses((status = 204, description = "View removed, admin only")),
+)]
+pub(crate) async fn delete(
+    _admin: AdminOnly,
+    ApiPath(id): ApiPath<Uuid>,
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    let result = sqlx::query("DELETE FROM saved_view WHERE id = $1")
+        .bind(id)
+        .execute(&state.db.pool)
+        .observe("view_delete")
+        .await
+        .map_err(AppError::DatabaseError);
+
+    match result {
+        Ok(done) if done.rows_affected() == 0 => view_not_found(&id.to_string()).into_response(),
+        Ok(_) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use serde_json::json;
+
+    fn view(endpoint: &str) -> SavedView {
+        SavedView {
+            id: Uuid::nil(),
+            name: "q1".to_string(),
+            endpoint: endpoint.to_string(),
+            params: [
+                ("from".to_string(), json!("2025-01-01")),
+                ("to".to_string(), json!("2025-03-31")),
+                ("per_account".to_string(), json!(true)),
+            ]
+            .into(),
+            owner: "alice".to_string(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn pairs(raw: &[(&str, &str)]) -> Vec<(String, String)> {
+        raw.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn test_url_params_override_view_params() {
+        let mut query = pairs(&[("to", "2025-02-28")]);
+        assert!(apply_view(&mut query, &view("/metrics"), "/api/v1/metrics").is_ok());
+        assert_eq!(query, pairs(&[("to", "2025-02-28"), ("from", "2025-01-01"), ("per_account", "true")]));
+    }
+
+    #[test]
+    fn test_view_is_rejected_on_another_route() {
+        let mut query = Vec::new();
+        match apply_view(&mut query, &view("/universe"), "/metrics") {
+            Err(err) => assert_eq!(err.status(), StatusCode::BAD_REQUEST),
+            Ok(()) => panic!("a /universe view was applied to /metrics"),
+        }
+        assert!(query.is_empty());
+    }
+}
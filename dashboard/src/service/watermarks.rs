@@ -1,4 +1,5 @@
-use axum::{extract::Query, extract::State, response::IntoResponse};
+use axum::{extract::State, response::IntoResponse};
+use chrono::Datelike;
 use std::sync::Arc;
 
 use crate::{
@@ -7,10 +8,20 @@ use crate::{
     models::strategy::Status,
 };
 
+use crate::openapi::WatermarksBody;
 use super::common::AppError;
-
+use super::extract::ApiQuery;
+use crate::telemetry::ObserveQuery;
+
+#[utoipa::path(
+    get,
+    path = "/watermarks",
+    tag = "analytics",
+    params(WatermarkRequest),
+    responses((status = 200, description = "Trade counts by period and watermark range", body = WatermarksBody)),
+)]
 pub(crate) async fn watermarks(
-    Query(request): Query<WatermarkRequest>,
+    ApiQuery(request): ApiQuery<WatermarkRequest>,
     State(state): State<Arc<AppState>>,
 ) -> impl IntoResponse {
     let year_start = request.to - chrono::Duration::days(365);
@@ -23,21 +34,27 @@ pub(crate) async fn watermarks(
     FROM
         strategy
     WHERE
-        entry_time >= $1
-    AND exit_time <= $2
+        (entry_time AT TIME ZONE $5)::date >= $1
+    AND (exit_time AT TIME ZONE $5)::date <= $2
     AND status = $3
     AND (risk->>'stats')::jsonb->>'pnl' IS NOT NULL
     AND ((risk->>'stats')::jsonb->>'pnl')::numeric > 0
     AND (risk->>'loss')::jsonb->>'watermark' IS NOT NULL
+    AND ($4::text IS NULL OR account->>'account_id' = $4)
     "#;
 
     let status = Status::Closed;
 
+    // Watermarks are percentages and P&L is only tested for sign, so no FX conversion applies
+
     let result = sqlx::query(query)
         .bind(year_start)
         .bind(request.to)
         .bind(Into::<i32>::into(status))
+        .bind(request.account_id)
+        .bind(state.calendar.timezone_name())
         .fetch_all(&state.db.pool)
+        .observe("watermarks")
         .await
         .map_err(AppError::DatabaseError);
 
@@ -81,11 +98,16 @@ pub(crate) async fn watermarks(
 
             let mut heatmap_data: HashMap<(String, String), i32> = HashMap::new();
 
+            // Weeks start on Monday and exits are bucketed by their trading session date
+            let week_origin = year_start
+                - chrono::Duration::days(year_start.weekday().num_days_from_monday() as i64);
+
             for (exit_time, watermark_f64) in row_data {
-                let days_from_start = (exit_time.date_naive() - year_start).num_days();
-                let week_number = (days_from_start / 7).max(0).min(51);
+                let session_date = state.calendar.booking_date(exit_time);
+                let days_from_start = (session_date - week_origin).num_days();
+                let week_number = (days_from_start / 7).clamp(0, 52);
 
-                let week_start = year_start + chrono::Duration::days(week_number * 7);
+                let week_start = week_origin + chrono::Duration::days(week_number * 7);
                 let time_label = format!("W{:02}-{}", week_number + 1, week_start.format("%m/%d"));
 
                 for (min, max, label) in &watermark_ranges {
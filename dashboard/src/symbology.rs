@@ -0,0 +1,318 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+use chrono::{Datelike, NaiveDate};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::models::{AssetType, settings::SymbologyConfig};
+
+// Shares per listed equity option contract
+const EQUITY_OPTION_MULTIPLIER: i64 = 100;
+
+static PARSER: OnceLock<SymbolParser> = OnceLock::new();
+
+/// Install the configured root table, later calls are ignored
+pub(crate) fn init(config: &SymbologyConfig) {
+    let _ = PARSER.set(SymbolParser::new(config));
+}
+
+pub(crate) fn parser() -> &'static SymbolParser {
+    PARSER.get_or_init(|| SymbolParser::new(&SymbologyConfig::default()))
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub(crate) enum OptionRight {
+    Call,
+    Put,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "kind")]
+pub(crate) enum Instrument {
+    Equity,
+    EquityOption {
+        expiry: NaiveDate,
+        right: OptionRight,
+        strike: Decimal,
+    },
+    Future {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        month: Option<u32>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        year: Option<i32>,
+    },
+    FutureOption {
+        // Underlying futures contract, e.g. /ESZ4
+        contract: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        expiry: Option<NaiveDate>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        right: Option<OptionRight>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        strike: Option<Decimal>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub(crate) struct ParsedSymbol {
+    pub raw: String,
+    // Aggregation key shared by every contract/expiry, e.g. /ES or AAPL
+    pub root: String,
+    pub instrument: Instrument,
+}
+
+fn month_from_code(code: char) -> Option<u32> {
+    Some(match code {
+        'F' => 1,
+        'G' => 2,
+        'H' => 3,
+        'J' => 4,
+        'K' => 5,
+        'M' => 6,
+        'N' => 7,
+        'Q' => 8,
+        'U' => 9,
+        'V' => 10,
+        'X' => 11,
+        'Z' => 12,
+        _ => return None,
+    })
+}
+
+// Single digit years resolve to the closest matching year around `pivot`
+fn year_from_digits(digits: &str, pivot: i32) -> Option<i32> {
+    let value: i32 = digits.parse().ok()?;
+    match digits.len() {
+        1 => {
+            let base = pivot - pivot.rem_euclid(10) + value;
+            [base - 10, base, base + 10].into_iter().min_by_key(|y| (y - pivot).abs())
+        }
+        2 => Some(2000 + value),
+        _ => None,
+    }
+}
+
+// YYMMDD followed by C/P and a strike, shared by OCC and futures option suffixes
+fn parse_option_suffix(suffix: &str) -> Option<(NaiveDate, OptionRight, &str)> {
+    if suffix.len() < 8 || !suffix.is_char_boundary(6) || !suffix.is_char_boundary(7) {
+        return None;
+    }
+    let expiry = NaiveDate::parse_from_str(&suffix[..6], "%y%m%d").ok()?;
+    let right = match &suffix[6..7] {
+        "C" => OptionRight::Call,
+        "P" => OptionRight::Put,
+        _ => return None,
+    };
+    Some((expiry, right, &suffix[7..]))
+}
+
+/// Parses equity, OCC option, futures and futures option symbols into roots
+#[derive(Debug)]
+pub(crate) struct SymbolParser {
+    // Longest first so /MES wins over /ME
+    roots: Vec<String>,
+    // Keyed by root without the leading slash
+    multipliers: HashMap<String, Decimal>,
+}
+
+fn normalize_root(root: &str) -> String {
+    root.trim_start_matches('/').to_uppercase()
+}
+
+impl SymbolParser {
+    pub fn new(config: &SymbologyConfig) -> Self {
+        let mut roots: Vec<String> = config.futures_roots.iter().map(|r| normalize_root(r)).collect();
+        roots.sort_by(|a, b| b.len().cmp(&a.len()).then(a.cmp(b)));
+        roots.dedup();
+        let multipliers = config
+            .contract_multipliers
+            .iter()
+            .map(|(root, multiplier)| (normalize_root(root), *multiplier))
+            .collect();
+        SymbolParser { roots, multipliers }
+    }
+
+    /// Dollar value of a one point move in one contract, 1 for unknown futures roots
+    pub fn multiplier(&self, root: &str, asset_type: AssetType) -> Decimal {
+        match asset_type {
+            AssetType::Equity => Decimal::ONE,
+            AssetType::EquityOption => Decimal::from(EQUITY_OPTION_MULTIPLIER),
+            AssetType::Future | AssetType::FutureOption => {
+                self.multipliers.get(&normalize_root(root)).copied().unwrap_or(Decimal::ONE)
+            }
+        }
+    }
+
+    /// Single digit contract years resolve to the decade closest to `as_of`, which
+    /// should be the date the contract was traded, e.g. the strategy's entry time
+    pub fn parse(&self, symbol: &str, as_of: NaiveDate) -> ParsedSymbol {
+        let pivot = as_of.year();
+        let raw = symbol.trim();
+        let (root, instrument) = if let Some(body) = raw.strip_prefix("./") {
+            self.parse_future_option(body, pivot)
+        } else if let Some(body) = raw.strip_prefix('/') {
+            let (root, month, year) = self.split_future(body, pivot);
+            (format!("/{root}"), Instrument::Future { month, year })
+        } else if let Some((underlying, instrument)) = Self::parse_occ(raw) {
+            (underlying, instrument)
+        } else {
+            (raw.to_string(), Instrument::Equity)
+        };
+
+        ParsedSymbol { raw: raw.to_string(), root, instrument }
+    }
+
+    // The root does not depend on the contract year, any reference date will do
+    pub fn root_of(&self, symbol: &str) -> String {
+        self.parse(symbol, NaiveDate::default()).root
+    }
+
+    // ESZ4 -> (ES, 12, 2024); bare roots such as ES yield no month/year
+    fn split_future(&self, body: &str, pivot: i32) -> (String, Option<u32>, Option<i32>) {
+        let split_expiry = |rest: &str| -> Option<(u32, i32)> {
+            let mut chars = rest.chars();
+            let month = month_from_code(chars.next()?)?;
+            let year = year_from_digits(chars.as_str(), pivot)?;
+            Some((month, year))
+        };
+
+        for root in &self.roots {
+            if let Some(rest) = body.strip_prefix(root.as_str()) {
+                if rest.is_empty() {
+                    return (root.clone(), None, None);
+                }
+                if let Some((month, year)) = split_expiry(rest) {
+                    return (root.clone(), Some(month), Some(year));
+                }
+            }
+        }
+
+        // Unknown root, assume <root><month code><1-2 digit year>
+        let digits = body.chars().rev().take_while(|c| c.is_ascii_digit()).count();
+        if (1..=2).contains(&digits) && body.len() > digits + 1 {
+            let code_at = body.len() - digits - 1;
+            if body.is_char_boundary(code_at)
+                && let Some((month, year)) = split_expiry(&body[code_at..])
+            {
+                return (body[..code_at].to_string(), Some(month), Some(year));
+            }
+        }
+        (body.to_string(), None, None)
+    }
+
+    // ./ESZ4 EW3F5 250117P5800
+    fn parse_future_option(&self, body: &str, pivot: i32) -> (String, Instrument) {
+        let mut tokens = body.split_whitespace();
+        let contract = tokens.next().unwrap_or_default();
+        let (root, _, _) = self.split_future(contract, pivot);
+        let option = tokens.last().and_then(parse_option_suffix);
+
+        let instrument = Instrument::FutureOption {
+            contract: format!("/{contract}"),
+            expiry: option.map(|(expiry, _, _)| expiry),
+            right: option.map(|(_, right, _)| right),
+            strike: option.and_then(|(_, _, strike)| Decimal::from_str(strike).ok()),
+        };
+        (format!("/{root}"), instrument)
+    }
+
+    // OCC: underlying padded to 6, YYMMDD, C/P, strike x1000 in 8 digits
+    fn parse_occ(raw: &str) -> Option<(String, Instrument)> {
+        if raw.len() < 16 || !raw.is_char_boundary(raw.len() - 15) {
+            return None;
+        }
+        let (underlying, suffix) = raw.split_at(raw.len() - 15);
+        let underlying = underlying.trim();
+        if underlying.is_empty() {
+            return None;
+        }
+        let (expiry, right, strike) = parse_option_suffix(suffix)?;
+        if strike.len() != 8 || !strike.chars().all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+        let strike = Decimal::from_str(strike).ok()? / Decimal::from(1000);
+        Some((underlying.to_string(), Instrument::EquityOption { expiry, right, strike }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parser() -> SymbolParser {
+        SymbolParser::new(&SymbologyConfig::default())
+    }
+
+    fn as_of() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 11, 1).unwrap()
+    }
+
+    #[test]
+    fn test_equity_and_occ_option() {
+        let p = parser();
+        assert_eq!(p.parse("SPY", as_of()).root, "SPY");
+
+        let parsed = p.parse("AAPL  250117C00150000", as_of());
+        assert_eq!(parsed.root, "AAPL");
+        assert_eq!(
+            parsed.instrument,
+            Instrument::EquityOption {
+                expiry: NaiveDate::from_ymd_opt(2025, 1, 17).unwrap(),
+                right: OptionRight::Call,
+                strike: Decimal::from(150),
+            }
+        );
+    }
+
+    #[test]
+    fn test_futures_roots_of_varying_length() {
+        let p = parser();
+        assert_eq!(p.root_of("/ESZ24"), "/ES");
+        assert_eq!(p.root_of("/MESH5"), "/MES");
+        assert_eq!(p.root_of("/ZNM25"), "/ZN");
+        assert_eq!(p.root_of("/6EU5"), "/6E");
+        assert_eq!(p.root_of("/ES"), "/ES");
+
+        let parsed = p.parse("/MESH25", as_of());
+        assert_eq!(parsed.instrument, Instrument::Future { month: Some(3), year: Some(2025) });
+    }
+
+    #[test]
+    fn test_unknown_future_root_and_future_option() {
+        let p = parser();
+        assert_eq!(p.root_of("/XYZK26"), "/XYZ");
+
+        let parsed = p.parse("./ESZ4 EW3F5 250117P5800", as_of());
+        assert_eq!(parsed.root, "/ES");
+        match parsed.instrument {
+            Instrument::FutureOption { contract, right, strike, .. } => {
+                assert_eq!(contract, "/ESZ4");
+                assert_eq!(right, Some(OptionRight::Put));
+                assert_eq!(strike, Some(Decimal::from(5800)));
+            }
+            other => panic!("unexpected instrument {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_multiplier_by_asset_type_and_root() {
+        let p = parser();
+        assert_eq!(p.multiplier("SPY", AssetType::Equity), Decimal::ONE);
+        assert_eq!(p.multiplier("SPY", AssetType::EquityOption), Decimal::from(100));
+        assert_eq!(p.multiplier("/ES", AssetType::FutureOption), Decimal::from(50));
+        assert_eq!(p.multiplier("/MYM", AssetType::Future), Decimal::new(5, 1));
+        assert_eq!(p.multiplier("/XYZ", AssetType::Future), Decimal::ONE);
+    }
+
+    #[test]
+    fn test_single_digit_years_pivot_on_the_trade_date() {
+        let p = parser();
+        let traded = |year| NaiveDate::from_ymd_opt(year, 6, 1).unwrap();
+        assert_eq!(p.parse("/ESZ4", traded(2024)).instrument, Instrument::Future { month: Some(12), year: Some(2024) });
+        assert_eq!(p.parse("/ESZ4", traded(2033)).instrument, Instrument::Future { month: Some(12), year: Some(2034) });
+        assert_eq!(p.parse("/ESH5", traded(2024)).instrument, Instrument::Future { month: Some(3), year: Some(2025) });
+    }
+}
@@ -0,0 +1,344 @@
+use axum::{
+    extract::{MatchedPath, Request, State},
+    http::{HeaderMap, StatusCode, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use futures::future::BoxFuture;
+use metrics::{Unit, counter, describe_counter, describe_gauge, describe_histogram, gauge, histogram};
+use metrics_exporter_prometheus::{Matcher, PrometheusBuilder, PrometheusHandle};
+use opentelemetry::{global, propagation::Extractor};
+use sqlx::postgres::PgQueryResult;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::task::JoinHandle;
+use tracing::{Instrument, Span, field, info_span};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+use crate::AppState;
+
+const PROMETHEUS_CONTENT_TYPE: &str = "text/plain; version=0.0.4";
+
+// Label for queries issued outside a request, e.g. the event listener and audit writer
+const BACKGROUND: &str = "background";
+
+const LATENCY_BUCKETS: &[f64] = &[0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0];
+const ROW_BUCKETS: &[f64] = &[0.0, 1.0, 10.0, 100.0, 1_000.0, 10_000.0, 100_000.0];
+
+tokio::task_local! {
+    // Matched route of the request being served, attributes queries to their handler
+    static HANDLER: Arc<str>;
+}
+
+/// Installs the global recorder, everything recorded before this is lost
+pub(crate) fn install() -> anyhow::Result<PrometheusHandle> {
+    let handle = PrometheusBuilder::new()
+        .set_buckets_for_metric(Matcher::Suffix("_seconds".to_string()), LATENCY_BUCKETS)?
+        .set_buckets_for_metric(Matcher::Full("dashboard_db_query_rows".to_string()), ROW_BUCKETS)?
+        .install_recorder()?;
+
+    describe_counter!("dashboard_http_requests_total", "Requests served by route and status");
+    describe_histogram!(
+        "dashboard_http_request_duration_seconds",
+        Unit::Seconds,
+        "Time until the response head was ready, by route and status"
+    );
+    describe_histogram!("dashboard_db_query_duration_seconds", Unit::Seconds, "Query time by handler and query");
+    describe_histogram!("dashboard_db_query_rows", Unit::Count, "Rows returned or affected by handler and query");
+    describe_gauge!("dashboard_db_pool_connections", Unit::Count, "Pool connections by state");
+    describe_gauge!("dashboard_db_pool_max_connections", Unit::Count, "Pool size limit");
+    describe_counter!("dashboard_cache_lookups_total", "In-memory cache lookups by cache and result");
+    describe_counter!("dashboard_log_entries_dropped_total", "Log entries the CloudWatch layer discarded on a full queue");
+    describe_gauge!("dashboard_log_backlog", Unit::Count, "Log entries queued for CloudWatch");
+
+    Ok(handle)
+}
+
+fn current_handler() -> Arc<str> {
+    HANDLER.try_with(Arc::clone).unwrap_or_else(|_| BACKGROUND.into())
+}
+
+/// Spawner for work a request fans out, e.g. GraphQL batch loads, so its queries
+/// stay attributed to the request's handler and nested under its span
+pub(crate) fn spawner() -> impl Fn(BoxFuture<'static, ()>) -> JoinHandle<()> + Send + Sync + 'static {
+    let handler = current_handler();
+    let span = Span::current();
+    move |future| tokio::spawn(HANDLER.scope(handler.clone(), future).instrument(span.clone()))
+}
+
+struct HeaderExtractor<'a>(&'a HeaderMap);
+
+impl Extractor for HeaderExtractor<'_> {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).and_then(|value| value.to_str().ok())
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.0.keys().map(|key| key.as_str()).collect()
+    }
+}
+
+pub(crate) fn cache_lookup(cache: &'static str, hit: bool) {
+    let result = if hit { "hit" } else { "miss" };
+    counter!("dashboard_cache_lookups_total", "cache" => cache, "result" => result).increment(1);
+}
+
+/// Counts requests and their latency by matched route and traces each one, joining
+/// the caller's trace when it sends a traceparent header. Unmatched requests fall
+/// through to the static frontend and are not recorded
+pub(crate) async fn track_requests(request: Request, next: Next) -> Response {
+    let started = Instant::now();
+    let method = request.method().to_string();
+    let route: Arc<str> = match request.extensions().get::<MatchedPath>() {
+        Some(path) => path.as_str().into(),
+        None => return next.run(request).await,
+    };
+
+    let span = info_span!(
+        "request",
+        otel.name = format!("{method} {route}"),
+        otel.kind = "server",
+        http.request.method = %method,
+        http.route = %route,
+        url.path = %request.uri().path(),
+        http.response.status_code = field::Empty,
+    );
+    let parent = global::get_text_map_propagator(|propagator| propagator.extract(&HeaderExtractor(request.headers())));
+    // Only fails when no OpenTelemetry layer is installed, the span is then local only
+    let _ = span.set_parent(parent);
+
+    let response = HANDLER.scope(route.clone(), next.run(request)).instrument(span.clone()).await;
+    // The OpenTelemetry layer exports u64 fields as strings, i64 as integers
+    span.record("http.response.status_code", i64::from(response.status().as_u16()));
+
+    let labels = [
+        ("method", method),
+        ("route", route.to_string()),
+        ("status", response.status().as_u16().to_string()),
+    ];
+    counter!("dashboard_http_requests_total", &labels).increment(1);
+    histogram!("dashboard_http_request_duration_seconds", &labels).record(started.elapsed().as_secs_f64());
+    response
+}
+
+/// Rows a query produced, recorded next to its duration
+pub(crate) trait RowCount {
+    fn row_count(&self) -> u64;
+}
+
+impl<T> RowCount for Vec<T> {
+    fn row_count(&self) -> u64 {
+        self.len() as u64
+    }
+}
+
+impl<T> RowCount for Option<T> {
+    fn row_count(&self) -> u64 {
+        self.is_some() as u64
+    }
+}
+
+impl RowCount for PgQueryResult {
+    fn row_count(&self) -> u64 {
+        self.rows_affected()
+    }
+}
+
+async fn timed<T, F>(query: &'static str, future: F, rows: fn(&T) -> u64) -> Result<T, sqlx::Error>
+where
+    F: Future<Output = Result<T, sqlx::Error>>,
+{
+    let handler = current_handler().to_string();
+    // Child of the request span, or a root span for background work
+    let span = info_span!(
+        "query",
+        otel.name = query,
+        otel.kind = "client",
+        otel.status_code = field::Empty,
+        db.system.name = "postgresql",
+        db.query.name = query,
+        db.response.returned_rows = field::Empty,
+    );
+    let started = Instant::now();
+    let result = future.instrument(span.clone()).await;
+
+    match &result {
+        Ok(value) => span.record("db.response.returned_rows", rows(value) as i64),
+        Err(_) => span.record("otel.status_code", "ERROR"),
+    };
+    let outcome = if result.is_ok() { "ok" } else { "error" };
+    histogram!("dashboard_db_query_duration_seconds", "handler" => handler.clone(), "query" => query, "outcome" => outcome)
+        .record(started.elapsed().as_secs_f64());
+    if let Ok(value) = &result {
+        histogram!("dashboard_db_query_rows", "handler" => handler, "query" => query).record(rows(value) as f64);
+    }
+    result
+}
+
+/// Records duration and row counts for sqlx futures, e.g.
+/// `.fetch_all(&state.db.pool).observe("universe").await`
+pub(crate) trait ObserveQuery<T>: Future<Output = Result<T, sqlx::Error>> + Sized {
+    fn observe(self, query: &'static str) -> impl Future<Output = Result<T, sqlx::Error>> + Send
+    where
+        Self: Send,
+        T: RowCount + Send;
+
+    // fetch_one either yields its row or fails
+    fn observe_one(self, query: &'static str) -> impl Future<Output = Result<T, sqlx::Error>> + Send
+    where
+        Self: Send,
+        T: Send;
+}
+
+impl<F, T> ObserveQuery<T> for F
+where
+    F: Future<Output = Result<T, sqlx::Error>>,
+{
+    fn observe(self, query: &'static str) -> impl Future<Output = Result<T, sqlx::Error>> + Send
+    where
+        Self: Send,
+        T: RowCount + Send,
+    {
+        timed(query, self, T::row_count)
+    }
+
+    fn observe_one(self, query: &'static str) -> impl Future<Output = Result<T, sqlx::Error>> + Send
+    where
+        Self: Send,
+        T: Send,
+    {
+        timed(query, self, |_| 1)
+    }
+}
+
+// Pool and log shipping state is sampled when scraped rather than on a timer
+fn sample(state: &AppState) {
+    let pool = &state.db.pool;
+    let idle = pool.num_idle();
+    gauge!("dashboard_db_pool_connections", "state" => "idle").set(idle as f64);
+    gauge!("dashboard_db_pool_connections", "state" => "in_use").set((pool.size() as usize).saturating_sub(idle) as f64);
+    gauge!("dashboard_db_pool_max_connections").set(pool.options().get_max_connections() as f64);
+
+    if let Some(logs) = &state.logs {
+        counter!("dashboard_log_entries_dropped_total").absolute(logs.dropped());
+        gauge!("dashboard_log_backlog").set(logs.backlog() as f64);
+    }
+}
+
+/// Prometheus text exposition, served away from /metrics which the trading metrics own
+pub(crate) async fn scrape(State(state): State<Arc<AppState>>) -> Response {
+    let Some(handle) = &state.prometheus else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    sample(&state);
+    handle.run_upkeep();
+    ([(header::CONTENT_TYPE, PROMETHEUS_CONTENT_TYPE)], handle.render()).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{Router, body::Body, middleware, routing::get};
+    use opentelemetry::trace::{SpanKind, TracerProvider};
+    use opentelemetry::{KeyValue, Value};
+    use opentelemetry_sdk::error::OTelSdkResult;
+    use opentelemetry_sdk::propagation::TraceContextPropagator;
+    use opentelemetry_sdk::trace::{SdkTracerProvider, SpanData, SpanExporter};
+    use std::sync::Mutex;
+    use tower::ServiceExt;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[derive(Clone, Debug, Default)]
+    struct CapturedSpans(Arc<Mutex<Vec<SpanData>>>);
+
+    impl SpanExporter for CapturedSpans {
+        async fn export(&self, batch: Vec<SpanData>) -> OTelSdkResult {
+            self.0.lock().unwrap().extend(batch);
+            Ok(())
+        }
+    }
+
+    // Runs `future` with spans exported as they end, the way the OTLP layer sees them
+    fn traced<F: Future>(future: F) -> Vec<SpanData> {
+        let spans = CapturedSpans::default();
+        let provider = SdkTracerProvider::builder().with_simple_exporter(spans.clone()).build();
+        let subscriber =
+            tracing_subscriber::registry().with(tracing_opentelemetry::layer().with_tracer(provider.tracer("test")));
+        global::set_text_map_propagator(TraceContextPropagator::new());
+
+        tracing::subscriber::with_default(subscriber, || {
+            let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+            runtime.block_on(future);
+        });
+        spans.0.lock().unwrap().clone()
+    }
+
+    fn attribute<'a>(span: &'a SpanData, key: &str) -> Option<&'a Value> {
+        span.attributes.iter().find(|kv: &&KeyValue| kv.key.as_str() == key).map(|kv| &kv.value)
+    }
+
+    #[test]
+    fn test_traceparent_becomes_the_request_parent() {
+        let spans = traced(async {
+            let app = Router::new()
+                .route("/universe", get(|| async { "ok" }))
+                .route_layer(middleware::from_fn(track_requests));
+            let request = axum::http::Request::get("/universe")
+                .header("traceparent", "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01")
+                .body(Body::empty())
+                .unwrap();
+            app.oneshot(request).await.unwrap();
+        });
+
+        let request = spans.iter().find(|s| s.span_kind == SpanKind::Server).unwrap();
+        assert_eq!(request.span_context.trace_id().to_string(), "4bf92f3577b34da6a3ce929d0e0e4736");
+        assert_eq!(request.parent_span_id.to_string(), "00f067aa0ba902b7");
+        assert_eq!(request.name, "GET /universe");
+        assert_eq!(attribute(request, "http.response.status_code"), Some(&Value::I64(200)));
+    }
+
+    #[test]
+    fn test_query_spans_record_returned_rows() {
+        let spans = traced(async {
+            let request = info_span!("request");
+            async {
+                let _ = async { Ok::<_, sqlx::Error>(vec![1, 2, 3]) }.observe("universe").await;
+            }
+            .instrument(request)
+            .await;
+        });
+
+        let query = spans.iter().find(|s| s.name == "universe").unwrap();
+        let request = spans.iter().find(|s| s.name == "request").unwrap();
+        assert_eq!(query.span_kind, SpanKind::Client);
+        assert_eq!(query.parent_span_id, request.span_context.span_id());
+        assert_eq!(attribute(query, "db.response.returned_rows"), Some(&Value::I64(3)));
+        assert_eq!(attribute(query, "db.query.name"), Some(&Value::from("universe")));
+    }
+
+    #[test]
+    fn test_queries_are_recorded_under_their_handler() {
+        let recorder = PrometheusBuilder::new().build_recorder();
+        let handle = recorder.handle();
+
+        metrics::with_local_recorder(&recorder, || {
+            let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+            runtime.block_on(HANDLER.scope("/universe".into(), async {
+                let rows = async { Ok::<_, sqlx::Error>(vec![1, 2, 3]) }.observe("universe").await;
+                assert_eq!(rows.unwrap().len(), 3);
+            }));
+            runtime.block_on(async {
+                let _ = async { Err::<Option<i32>, _>(sqlx::Error::RowNotFound) }.observe("listener").await;
+            });
+        });
+
+        let rendered = handle.render();
+        assert!(rendered.contains(r#"dashboard_db_query_rows_sum{handler="/universe",query="universe"} 3"#), "{rendered}");
+        assert!(
+            rendered.contains(r#"dashboard_db_query_duration_seconds_count{handler="background",query="listener",outcome="error"} 1"#),
+            "{rendered}"
+        );
+    }
+}
+
@@ -95,7 +95,8 @@ async fn main() -> Result<(), Error> {
     let _ = aws_logging::init_cloudwatch_logger(&aws_logging::LoggingConfig { 
         log_group: "trading-tools".to_string(), 
         log_stream: "oauth-proxy".to_string(), 
-        level: "INFO".to_string() 
+        level: "INFO".to_string(),
+        otlp: None,
     });
     lambda_http::run(service_fn(oauth_endpoints)).await?;
     Ok(())